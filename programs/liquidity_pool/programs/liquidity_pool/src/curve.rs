@@ -0,0 +1,432 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::math::{mul_div_floor, mul_div_u128, sqrt_product, sum_of_products_div};
+use crate::state::fixed_point_price;
+
+// Pricing curves.
+// Constant-product pools price along x * y = k. StableSwap pools (Curve's invariant for two
+// tokens) price along
+//     4A(x + y) + D = 4AD + D^3 / (4xy)
+// which stays close to 1:1 around balanced reserves and bends into x * y = k far from them; the
+// amplification A sets how wide the flat part is. Balances are scaled to the larger of the two
+// mints' decimals first, so a 6-decimal and a 9-decimal stablecoin still trade 1:1.
+// D and y have no closed form and are found with Newton's method, capped at MAX_ITERATIONS so a
+// pathological pool fails with `StableSwapNotConverged` instead of running out of compute.
+
+pub const MIN_AMPLIFICATION: u64 = 1;
+pub const MAX_AMPLIFICATION: u64 = 1_000_000;
+// A ramp may change A by at most this factor, like Curve's MAX_A_CHANGE
+pub const MAX_AMPLIFICATION_CHANGE: u64 = 10;
+// A ramp lasts at least this long and can't start sooner than this after the previous one
+pub const MIN_RAMP_DURATION: i64 = 86_400;
+// Balanced pools converge in a handful of iterations and even a 10^12 imbalance in about 30
+pub const MAX_ITERATIONS: usize = 64;
+
+// Curve a pool prices with, oriented for one swap direction: "in" is token A unless `reversed`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    ConstantProduct,
+    StableSwap(StableSwap),
+}
+
+impl Curve {
+    // Same curve with the input and output tokens swapped
+    pub fn reversed(self) -> Self {
+        match self {
+            Curve::ConstantProduct => Curve::ConstantProduct,
+            Curve::StableSwap(stable) => Curve::StableSwap(StableSwap {
+                rate_in: stable.rate_out,
+                rate_out: stable.rate_in,
+                ..stable
+            }),
+        }
+    }
+
+    // Output for `amount_in` (fee already taken), rounded down
+    pub fn amount_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        match self {
+            Curve::ConstantProduct => {
+                let denominator = reserve_in.checked_add(amount_in).ok_or(ErrorCode::Overflow)?;
+                require!(denominator > 0, ErrorCode::PoolEmpty);
+                mul_div_floor(reserve_out, amount_in, denominator)
+            }
+            Curve::StableSwap(stable) => stable.amount_out(amount_in, reserve_in, reserve_out),
+        }
+    }
+
+    // Input (before the fee) needed for `amount_out`, rounded up
+    pub fn amount_in(&self, amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(amount_out < reserve_out, ErrorCode::InsufficientPoolReserves);
+        match self {
+            // ceil(reserve_in * amount_out / (reserve_out - amount_out))
+            Curve::ConstantProduct => {
+                let product = reserve_in as u128 * amount_out as u128;
+                let amount_in = product.div_ceil((reserve_out - amount_out) as u128);
+                u64::try_from(amount_in).map_err(|_| error!(ErrorCode::Overflow))
+            }
+            Curve::StableSwap(stable) => stable.amount_in(amount_out, reserve_in, reserve_out),
+        }
+    }
+
+    // LP tokens backing the first deposit, before the locked minimum is taken out: the geometric
+    // mean on constant-product pools, the invariant D (in normalised units) on StableSwap pools
+    pub fn initial_liquidity(&self, amount_a: u64, amount_b: u64) -> Result<u64> {
+        match self {
+            Curve::ConstantProduct => Ok(sqrt_product(amount_a, amount_b)),
+            Curve::StableSwap(stable) => {
+                let d = stable.invariant(amount_a, amount_b)?;
+                u64::try_from(d).map_err(|_| error!(ErrorCode::Overflow))
+            }
+        }
+    }
+}
+
+// StableSwap parameters at one point in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StableSwap {
+    pub amplification: u64,
+    pub rate_in: u128,  // Scales the input token's amounts to the common decimals
+    pub rate_out: u128, // Scales the output token's amounts to the common decimals
+}
+
+impl StableSwap {
+    // Parameters for an A to B swap between mints with the given decimals
+    pub fn new(amplification: u64, decimals_a: u8, decimals_b: u8) -> Result<Self> {
+        require!(
+            (MIN_AMPLIFICATION..=MAX_AMPLIFICATION).contains(&amplification),
+            ErrorCode::InvalidAmplification
+        );
+        let (rate_in, rate_out) = decimal_rates(decimals_a, decimals_b)?;
+        Ok(Self { amplification, rate_in, rate_out })
+    }
+
+    // Reserves in common decimals
+    fn normalise(&self, reserve_in: u64, reserve_out: u64) -> Result<(u128, u128)> {
+        let x = (reserve_in as u128).checked_mul(self.rate_in).ok_or(ErrorCode::Overflow)?;
+        let y = (reserve_out as u128).checked_mul(self.rate_out).ok_or(ErrorCode::Overflow)?;
+        Ok((x, y))
+    }
+
+    // Invariant D of the given reserves, 0 when either side is empty
+    pub fn invariant(&self, reserve_in: u64, reserve_out: u64) -> Result<u128> {
+        let (x, y) = self.normalise(reserve_in, reserve_out)?;
+        compute_d(self.amplification, x, y)
+    }
+
+    // Curve's get_dy: the new output balance is solved for, then one more unit is kept back so
+    // the solver's rounding never pays out more than the invariant allows
+    fn amount_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(reserve_in > 0 && reserve_out > 0, ErrorCode::PoolEmpty);
+        let (x, y) = self.normalise(reserve_in, reserve_out)?;
+        let d = compute_d(self.amplification, x, y)?;
+        let new_reserve_in = reserve_in.checked_add(amount_in).ok_or(ErrorCode::Overflow)?;
+        let (new_x, _) = self.normalise(new_reserve_in, 0)?;
+        let new_y = compute_y(self.amplification, new_x, d)?;
+        let dy = y.saturating_sub(new_y).saturating_sub(1);
+        // Below `reserve_out`: new_y > 0, so dy < y = reserve_out * rate_out
+        Ok((dy / self.rate_out) as u64)
+    }
+
+    // The reverse: the new input balance is solved for, rounded up by one unit
+    fn amount_in(&self, amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        require!(reserve_in > 0 && reserve_out > 0, ErrorCode::PoolEmpty);
+        let (x, y) = self.normalise(reserve_in, reserve_out)?;
+        let d = compute_d(self.amplification, x, y)?;
+        let (_, new_y) = self.normalise(0, reserve_out - amount_out)?;
+        let new_x = compute_y(self.amplification, new_y, d)?;
+        let dx = new_x.saturating_sub(x).checked_add(1).ok_or(ErrorCode::Overflow)?;
+        u64::try_from(dx.div_ceil(self.rate_in)).map_err(|_| error!(ErrorCode::Overflow))
+    }
+}
+
+// Scale factors taking amounts of mints with the given decimals to the larger of the two
+pub fn decimal_rates(decimals_a: u8, decimals_b: u8) -> Result<(u128, u128)> {
+    let decimals = decimals_a.max(decimals_b);
+    let rate = |decimals_token: u8| {
+        10u128
+            .checked_pow((decimals - decimals_token) as u32)
+            .ok_or(error!(ErrorCode::Overflow))
+    };
+    Ok((rate(decimals_a)?, rate(decimals_b)?))
+}
+
+// Marginal price of x in y on the StableSwap curve through balances (x, y), UQ64.64: the slope
+// of the invariant, -dy/dx = (Ann + D^3 / 4x^2y) / (Ann + D^3 / 4xy^2). Multiplied out,
+//     price = y * (4Ann * x + r) / (x * (4Ann * y + r)),  r = D^3 / xy
+// which is 1 at balance and tends to y / x as A goes to 0. Saturates at the top of the range.
+pub fn stable_spot_price(amplification: u64, x: u128, y: u128) -> Result<u128> {
+    require!(x > 0 && y > 0, ErrorCode::PoolEmpty);
+    let d = compute_d(amplification, x, y)?;
+    // Divided by the smaller balance first, like D_P in `compute_d`
+    let (small, large) = (x.min(y), x.max(y));
+    let r = mul_div_u128(mul_div_u128(d, d, small)?, d, large)?;
+    let ann4 = amplification as u128 * 16;
+    let weight = |balance: u128| {
+        ann4.checked_mul(balance)
+            .and_then(|weighted| weighted.checked_add(r))
+            .ok_or(error!(ErrorCode::Overflow))
+    };
+    Ok(mul_div_u128(fixed_point_price(y, x), weight(x)?, weight(y)?).unwrap_or(u128::MAX))
+}
+
+// Curve's get_D for two tokens: Newton's method on the invariant, starting from x + y.
+//     D' = (Ann * S + 2 * D_P) * D / ((Ann - 1) * D + 3 * D_P),  D_P = D^3 / (4xy),  Ann = 4A
+pub fn compute_d(amplification: u64, x: u128, y: u128) -> Result<u128> {
+    require!(amplification > 0, ErrorCode::InvalidAmplification);
+    if x == 0 || y == 0 {
+        return Ok(0);
+    }
+    let sum = x.checked_add(y).ok_or(ErrorCode::Overflow)?;
+    // D_P is built dividing by the smaller balance first; the other order loses enough precision
+    // on imbalanced pools that the iteration never settles within 1
+    let (small, large) = (x.min(y), x.max(y));
+    let (small2, large2) = (small.checked_mul(2).ok_or(ErrorCode::Overflow)?, large.checked_mul(2).ok_or(ErrorCode::Overflow)?);
+    let ann = amplification as u128 * 4;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = mul_div_u128(mul_div_u128(d, d, small2)?, d, large2)?;
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|n| n.checked_add(d_p.checked_mul(2)?))
+            .ok_or(ErrorCode::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)
+            .and_then(|n| n.checked_add(d_p.checked_mul(3)?))
+            .ok_or(ErrorCode::Overflow)?;
+        let previous = d;
+        d = mul_div_u128(numerator, d, denominator)?;
+        if d.abs_diff(previous) <= 1 {
+            return Ok(d);
+        }
+    }
+    err!(ErrorCode::StableSwapNotConverged)
+}
+
+// Curve's get_y for two tokens: the other balance on the invariant D when one balance is `x`.
+//     y' = (y^2 + c) / (2y + b - D),  c = D^3 / (4x * Ann),  b = x + D / Ann
+// c outgrows u128 on large pools, so both sides are multiplied by 2Ann and c is never formed:
+//     y' = (2Ann * y^2 + k * D) / (2Ann * (2y + b - D)),  k = D^2 / 2x
+pub fn compute_y(amplification: u64, x: u128, d: u128) -> Result<u128> {
+    require!(amplification > 0, ErrorCode::InvalidAmplification);
+    require!(x > 0, ErrorCode::PoolEmpty);
+    let ann = amplification as u128 * 4;
+    let k = mul_div_u128(d, d, x.checked_mul(2).ok_or(ErrorCode::Overflow)?)?;
+    let b = x.checked_add(d / ann).ok_or(ErrorCode::Overflow)?;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let denominator = y
+            .checked_mul(2)
+            .and_then(|n| n.checked_add(b))
+            .and_then(|n| n.checked_sub(d))
+            .and_then(|n| n.checked_mul(ann * 2))
+            .ok_or(ErrorCode::Overflow)?;
+        let previous = y;
+        let ann2_y = (ann * 2).checked_mul(y).ok_or(ErrorCode::Overflow)?;
+        y = sum_of_products_div(ann2_y, y, k, d, denominator)?;
+        if y.abs_diff(previous) <= 1 {
+            return Ok(y);
+        }
+    }
+    err!(ErrorCode::StableSwapNotConverged)
+}
+
+// Amplification `now` while ramping linearly from `initial` at `start` to `target` at `end`
+pub fn ramped_amplification(initial: u64, target: u64, start: i64, end: i64, now: i64) -> u64 {
+    if now >= end {
+        return target;
+    }
+    if now <= start {
+        return initial;
+    }
+    let elapsed = (now - start) as u128;
+    let duration = (end - start) as u128;
+    // Between the two end points, so it fits in u64
+    if target >= initial {
+        initial + ((target - initial) as u128 * elapsed / duration) as u64
+    } else {
+        initial - ((initial - target) as u128 * elapsed / duration) as u64
+    }
+}
+
+// A new ramp from `current` to `target` ending at `end`, `now` being `last_start` or later.
+// Rules follow Curve's ramp_A: at least a day long, not within a day of the previous ramp's start
+// and at most a 10x change either way, so LPs can't be moved along the curve abruptly.
+pub fn validate_ramp(current: u64, target: u64, last_start: i64, now: i64, end: i64) -> Result<()> {
+    require!(
+        (MIN_AMPLIFICATION..=MAX_AMPLIFICATION).contains(&target),
+        ErrorCode::InvalidAmplification
+    );
+    require!(
+        now >= last_start.saturating_add(MIN_RAMP_DURATION) && end >= now.saturating_add(MIN_RAMP_DURATION),
+        ErrorCode::InvalidAmplificationRamp
+    );
+    require!(
+        target.saturating_mul(MAX_AMPLIFICATION_CHANGE) >= current
+            && target <= current.saturating_mul(MAX_AMPLIFICATION_CHANGE),
+        ErrorCode::InvalidAmplificationRamp
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 20_000;
+
+    // Deterministic xorshift64 generator
+    struct Inputs(u64);
+
+    impl Inputs {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        // 1..=u64::MAX >> shift for a random shift, so every magnitude is hit
+        fn amount(&mut self, max_shift: u64) -> u64 {
+            let shift = self.next() % max_shift;
+            (self.next() >> shift).max(1)
+        }
+    }
+
+    fn stable(amplification: u64) -> Curve {
+        Curve::StableSwap(StableSwap::new(amplification, 6, 6).unwrap())
+    }
+
+    #[test]
+    fn balanced_invariant_is_the_sum() {
+        for amplification in [1, 100, MAX_AMPLIFICATION] {
+            let d = compute_d(amplification, 1_000_000_000, 1_000_000_000).unwrap();
+            assert!(d.abs_diff(2_000_000_000) <= 1);
+        }
+        assert_eq!(compute_d(100, 0, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn balanced_swaps_trade_close_to_one_to_one() {
+        let reserve = 1_000_000_000_000; // 1M tokens with 6 decimals
+        let amount_in = 10_000_000_000;  // 1% of a side
+        let stable_out = stable(100).amount_out(amount_in, reserve, reserve).unwrap();
+        let product_out = Curve::ConstantProduct.amount_out(amount_in, reserve, reserve).unwrap();
+        assert!(stable_out < amount_in && stable_out > amount_in - amount_in / 10_000);
+        assert!(stable_out > product_out);
+        // Low amplification moves towards the constant-product price
+        let flatter_out = stable(1).amount_out(amount_in, reserve, reserve).unwrap();
+        assert!(flatter_out < stable_out && flatter_out > product_out);
+    }
+
+    #[test]
+    fn decimals_are_normalised() {
+        // 1M tokens of each, A with 6 decimals and B with 9
+        let curve = Curve::StableSwap(StableSwap::new(100, 6, 9).unwrap());
+        let (reserve_a, reserve_b) = (1_000_000_000_000, 1_000_000_000_000_000);
+        let out_b = curve.amount_out(1_000_000, reserve_a, reserve_b).unwrap();
+        assert!(out_b < 1_000_000_000 && out_b > 999_000_000);
+        let out_a = curve.reversed().amount_out(1_000_000_000, reserve_b, reserve_a).unwrap();
+        assert!(out_a < 1_000_000 && out_a > 999_000);
+        // Exact-out asks at least what exact-in gives for the same trade
+        let in_a = curve.amount_in(out_b, reserve_a, reserve_b).unwrap();
+        assert!(in_a <= 1_000_000 && in_a > 999_000);
+        assert_eq!(curve.initial_liquidity(1_000_000, 1_000_000_000).unwrap(), 2_000_000_000);
+    }
+
+    // Rounding always leaves the invariant where it was or higher, so no sequence of swaps
+    // drains value from LPs. Pools too lopsided for D to fit are errors, never panics.
+    #[test]
+    fn swaps_never_decrease_the_invariant() {
+        let mut inputs = Inputs(0x9E37_79B9_7F4A_7C15);
+        let mut checked = 0;
+        for _ in 0..SAMPLES {
+            let amplification = inputs.next() % MAX_AMPLIFICATION + 1;
+            let decimals_in = (inputs.next() % 10) as u8;
+            let decimals_out = (inputs.next() % 10) as u8;
+            let swap = StableSwap::new(amplification, decimals_in, decimals_out).unwrap();
+            let curve = Curve::StableSwap(swap);
+            let (reserve_in, reserve_out) = (inputs.amount(64), inputs.amount(64));
+            let amount = inputs.amount(64);
+            let Ok(d) = swap.invariant(reserve_in, reserve_out) else { continue };
+
+            if let Ok(out) = curve.amount_out(amount, reserve_in, reserve_out) {
+                assert!(out < reserve_out);
+                if let Ok(after) = swap.invariant(reserve_in + amount, reserve_out - out) {
+                    assert!(after >= d, "exact in {amount}: D {d} -> {after}");
+                    checked += 1;
+                }
+            }
+            let out = amount % reserve_out;
+            if let Ok(amount_in) = curve.amount_in(out, reserve_in, reserve_out) {
+                let after = reserve_in.checked_add(amount_in).map(|new_reserve_in| swap.invariant(new_reserve_in, reserve_out - out));
+                if let Some(Ok(after)) = after {
+                    assert!(after >= d, "exact out {out}: D {d} -> {after}");
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > SAMPLES, "only {checked} swaps checked");
+    }
+
+    #[test]
+    fn solver_converges_within_the_iteration_cap_for_realistic_pools() {
+        let mut inputs = Inputs(0xD1B5_4A32_D192_ED03);
+        for _ in 0..SAMPLES {
+            let amplification = inputs.next() % 5_000 + 1;
+            // Up to 10^6 imbalance between two balances of 18 decimal tokens
+            let x = inputs.amount(40) as u128 * 1_000_000_000;
+            let y = x / (inputs.next() % 1_000_000 + 1) as u128 + 1;
+            let d = compute_d(amplification, x, y).unwrap();
+            let y_solved = compute_y(amplification, x, d).unwrap();
+            assert!(y_solved.abs_diff(y) <= y / 1_000_000 + 2, "A={amplification} x={x} y={y}: {y_solved}");
+        }
+    }
+
+    #[test]
+    fn spot_price_is_the_slope_of_the_curve() {
+        const ONE: u128 = 1 << 64;
+        let balance = 1_000_000_000_000u128;
+        for amplification in [1, 100, MAX_AMPLIFICATION] {
+            let price = stable_spot_price(amplification, balance, balance).unwrap();
+            assert!(price.abs_diff(ONE) <= ONE / 1_000_000_000, "A={amplification}: {price}");
+        }
+
+        // Off balance it matches a small swap's rate and lies between 1 and the reserve ratio
+        let (reserve_a, reserve_b) = (1_000_000_000_000u64, 3_000_000_000_000u64);
+        let price = stable_spot_price(100, reserve_a as u128, reserve_b as u128).unwrap();
+        let amount_in = 1_000_000;
+        let out = stable(100).amount_out(amount_in, reserve_a, reserve_b).unwrap();
+        let swap_rate = fixed_point_price(out as u128, amount_in as u128);
+        assert!(price > ONE && price < 3 * ONE);
+        assert!(price.abs_diff(swap_rate) <= price / 100_000, "{price} vs {swap_rate}");
+        let inverse = stable_spot_price(100, reserve_b as u128, reserve_a as u128).unwrap();
+        assert!(mul_div_u128(price, inverse, ONE).unwrap().abs_diff(ONE) <= ONE / 1_000_000);
+    }
+
+    #[test]
+    fn amplification_ramps_linearly() {
+        assert_eq!(ramped_amplification(100, 200, 1_000, 2_000, 500), 100);
+        assert_eq!(ramped_amplification(100, 200, 1_000, 2_000, 1_500), 150);
+        assert_eq!(ramped_amplification(200, 100, 1_000, 2_000, 1_250), 175);
+        assert_eq!(ramped_amplification(100, 200, 1_000, 2_000, 2_000), 200);
+        assert_eq!(ramped_amplification(100, 100, 0, 0, 10), 100);
+    }
+
+    #[test]
+    fn ramps_are_bounded() {
+        let now = 10 * MIN_RAMP_DURATION;
+        let end = now + MIN_RAMP_DURATION;
+        assert!(validate_ramp(100, 1_000, 0, now, end).is_ok());
+        assert!(validate_ramp(100, 10, 0, now, end).is_ok());
+        let ramp_error = Err(ErrorCode::InvalidAmplificationRamp.into());
+        assert_eq!(validate_ramp(100, 1_001, 0, now, end), ramp_error);
+        assert_eq!(validate_ramp(100, 9, 0, now, end), ramp_error);
+        assert_eq!(validate_ramp(100, 200, 0, now, end - 1), ramp_error);
+        assert_eq!(validate_ramp(100, 200, now - MIN_RAMP_DURATION + 1, now, end), ramp_error);
+        assert_eq!(validate_ramp(100, 0, 0, now, end), Err(ErrorCode::InvalidAmplification.into()));
+        assert_eq!(
+            validate_ramp(MAX_AMPLIFICATION, MAX_AMPLIFICATION + 1, 0, now, end),
+            Err(ErrorCode::InvalidAmplification.into())
+        );
+    }
+}
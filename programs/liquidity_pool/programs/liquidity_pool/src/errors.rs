@@ -58,11 +58,110 @@ pub enum ErrorCode {
     PoolIdMismatch,
 
     #[msg("Invalid emitter chain ID in VAA.")]
-    InvalidEmitterChain, // Placeholder for future check
+    InvalidEmitterChain,
 
     #[msg("Invalid emitter address in VAA.")]
-    InvalidEmitterAddress, // Placeholder for future check
+    InvalidEmitterAddress,
 
     #[msg("Received VAA with an unsupported payload kind.")]
     UnsupportedPayloadKind,
+
+    #[msg("Swap amount must be greater than zero.")]
+    ZeroSwapAmount,
+
+    #[msg("Swap would produce zero output tokens.")]
+    ZeroSwapOutput,
+
+    #[msg("Fee percentage must not exceed 10000 basis points.")]
+    InvalidFeePercentage,
+
+    #[msg("Pool reserves cannot cover the requested output amount.")]
+    InsufficientPoolReserves,
+
+    #[msg("The program is globally paused.")]
+    ProgramPaused,
+
+    #[msg("Fee percentage is not one of the allowed fee tiers.")]
+    FeeTierNotAllowed,
+
+    #[msg("Fee tiers must be non-empty, unique and within the maximum count.")]
+    InvalidFeeTiers,
+
+    #[msg("Remote recipient address must not be empty.")]
+    InvalidRemoteRecipient,
+
+    #[msg("Payload protocol version is not supported.")]
+    UnsupportedPayloadVersion,
+
+    #[msg("Payload is addressed to a different chain.")]
+    InvalidTargetChain,
+
+    #[msg("Pool is not linked to a remote pool.")]
+    PoolNotLinked,
+
+    #[msg("Message does not come from the pool's linked remote pool.")]
+    PoolLinkMismatch,
+
+    #[msg("Rate limit window and release delay must be positive and at most 30 days.")]
+    InvalidRateLimit,
+
+    #[msg("Outflow exceeds the rate limit.")]
+    RateLimitExceeded,
+
+    #[msg("Bridge request is not delayed.")]
+    BridgeRequestNotDelayed,
+
+    #[msg("Delayed bridge request cannot be released yet.")]
+    BridgeRequestLocked,
+
+    #[msg("Bridge request has not failed.")]
+    BridgeRequestNotFailed,
+
+    #[msg("Only failed deposits and the surplus of completed deposits can be refunded.")]
+    BridgeRequestNotRefundable,
+
+    #[msg("Oracle capacity must be between 1 and 250 observations.")]
+    InvalidOracleCapacity,
+
+    #[msg("Observation windows must be positive and at most 24 per call.")]
+    InvalidObservationWindow,
+
+    #[msg("Requested window is older than the oracle's history.")]
+    ObservationTooOld,
+
+    #[msg("Division by zero.")]
+    DivisionByZero,
+
+    #[msg("First deposit is too small to cover the locked minimum liquidity.")]
+    InsufficientInitialLiquidity,
+
+    #[msg("Mint has a Token-2022 extension pools don't support.")]
+    UnsupportedMintExtension,
+
+    #[msg("User token account missing; only a native SOL side can use the wSOL account instead.")]
+    MissingUserTokenAccount,
+
+    #[msg("Token mints must be in canonical order (token_a_mint < token_b_mint).")]
+    MintsNotSorted,
+
+    #[msg("Pool ID doesn't match the one derived from the mints and fee tier.")]
+    InvalidPoolId,
+
+    #[msg("Amplification is out of range or not valid for the curve type.")]
+    InvalidAmplification,
+
+    #[msg("Amplification ramp is too fast, too short or starts too soon after the last one.")]
+    InvalidAmplificationRamp,
+
+    #[msg("Pool doesn't use the StableSwap curve.")]
+    NotStableSwapPool,
+
+    #[msg("StableSwap invariant didn't converge.")]
+    StableSwapNotConverged,
+
+    #[msg("Pool still has reserves on the linked chain and can't be relinked.")]
+    RemoteReservesOutstanding,
+
+    #[msg("Pool has a price oracle that must be passed to swaps.")]
+    OracleRequired,
 }
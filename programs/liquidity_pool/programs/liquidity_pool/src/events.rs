@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use crate::state::{BridgeFailure, BridgeRequest, BridgeStatus, CurveType, Pool, PoolSnapshot, PoolStatus};
+
+// Reserve-changing events carry the pool state before and after the change
+// (see PoolSnapshot), so indexers can rebuild pool history from events alone.
+
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub pool_id: [u8; 32],
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee_percentage: u64,
+    pub curve_type: CurveType,
+    pub amplification: u64,         // 0 on constant-product pools
+    pub linked_chain_id: u16,       // 0 unless created from a remote factory's VAA
+    pub linked_address: [u8; 32],
+    pub timestamp: i64,
+}
+
+impl PoolCreated {
+    pub fn new(key: Pubkey, pool: &Pool) -> Self {
+        PoolCreated {
+            pool: key,
+            pool_id: pool.pool_id,
+            token_a_mint: pool.token_a_mint,
+            token_b_mint: pool.token_b_mint,
+            lp_mint: pool.lp_mint,
+            fee_percentage: pool.fee_percentage,
+            curve_type: pool.curve_type,
+            amplification: pool.amp_target,
+            linked_chain_id: pool.linked_chain_id,
+            linked_address: pool.linked_address,
+            timestamp: pool.last_updated_at,
+        }
+    }
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,               // Receiver of the LP tokens
+    pub source_chain: u16,          // Chain the tokens were deposited on
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_minted: u64,
+    pub before: PoolSnapshot,
+    pub after: PoolSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,               // Solana owner of the burned LP tokens, or the paid recipient
+    pub target_chain: u16,          // Chain the tokens are paid out on
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_burned: u64,             // 0 when the LP tokens were burned on the remote chain
+    pub before: PoolSnapshot,
+    pub after: PoolSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Swap {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub total_fee: u64,
+    pub protocol_fee: u64,
+    pub before: PoolSnapshot,
+    pub after: PoolSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaaProcessed {
+    pub pool: Pubkey,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub op: u8,                     // BridgePayload op code
+    pub status: BridgeStatus,       // Completed, Delayed or Failed
+    pub failure_reason: BridgeFailure,
+    pub timestamp: i64,
+}
+
+impl VaaProcessed {
+    pub fn new(bridge_request: &BridgeRequest, op: u8) -> Self {
+        VaaProcessed {
+            pool: bridge_request.pool,
+            emitter_chain: bridge_request.emitter_chain,
+            emitter_address: bridge_request.emitter_address,
+            sequence: bridge_request.wormhole_sequence,
+            op,
+            status: bridge_request.status,
+            failure_reason: bridge_request.failure_reason,
+            timestamp: bridge_request.created_at,
+        }
+    }
+}
+
+#[event]
+pub struct BridgeRequestStatusChanged {
+    pub bridge_request: Pubkey,
+    pub emitter_chain: u16,
+    pub sequence: u64,
+    pub old_status: BridgeStatus,
+    pub new_status: BridgeStatus,
+    pub timestamp: i64,
+}
+
+impl BridgeRequestStatusChanged {
+    pub fn new(key: Pubkey, bridge_request: &BridgeRequest, old_status: BridgeStatus, timestamp: i64) -> Self {
+        BridgeRequestStatusChanged {
+            bridge_request: key,
+            emitter_chain: bridge_request.emitter_chain,
+            sequence: bridge_request.wormhole_sequence,
+            old_status,
+            new_status: bridge_request.status,
+            timestamp,
+        }
+    }
+}
+
+// Deposit (or the surplus of one) sent back to the chain it came from
+#[event]
+pub struct DepositRefunded {
+    pub bridge_request: Pubkey,
+    pub pool: Pubkey,
+    pub target_chain: u16,
+    pub refund_address: [u8; 32],
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesCollected {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolStatusChanged {
+    pub pool: Pubkey,
+    pub old_status: PoolStatus,
+    pub new_status: PoolStatus,
+    pub timestamp: i64,
+}
+
+// Amplification ramps of StableSwap pools; a stopped ramp is one that ends at `start`
+#[event]
+pub struct AmplificationRamped {
+    pub pool: Pubkey,
+    pub initial_amplification: u64,
+    pub target_amplification: u64,
+    pub start: i64,
+    pub end: i64,
+}
+
+// Pool link set by the admin; the old link is all zeroes on a first link
+#[event]
+pub struct PoolLinked {
+    pub pool: Pubkey,
+    pub old_chain_id: u16,
+    pub old_address: [u8; 32],
+    pub linked_chain_id: u16,
+    pub linked_address: [u8; 32],
+    pub emitter: Pubkey,            // Pool's emitter, announced to the linked pool
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeeUpdated {
+    pub pool: Pubkey,
+    pub old_protocol_fee_share: u64,
+    pub new_protocol_fee_share: u64,
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+// Global config after an update
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fee_tiers: Vec<u64>,
+    pub default_protocol_fee_share: u64,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+// Trusted emitter of a chain; the old address is all zeroes when registered, the new one when removed
+#[event]
+pub struct ForeignEmitterChanged {
+    pub chain: u16,
+    pub old_address: [u8; 32],
+    pub new_address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolRateLimitSet {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub capacity: u64,
+    pub window_seconds: i64,
+    pub release_delay: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChainRateLimitSet {
+    pub chain: u16,
+    pub mint: Pubkey,
+    pub capacity: u64,
+    pub window_seconds: i64,
+    pub release_delay: i64,
+    pub timestamp: i64,
+}
@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken, // Import AssociatedToken
-    token::{self, Mint, Token, TokenAccount, Transfer, MintTo}
-};
-use crate::state::Pool;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{Config, Pool};
 use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
+use crate::events::LiquidityAdded;
+use crate::curve::Curve;
+use crate::math::mul_div_floor;
+use crate::native_sol::{UserTokenAccount, WSOL_SEED};
+use crate::token_extensions::{amount_received, amount_to_send};
+use wormhole_anchor_sdk::wormhole;
 
     #[derive(Accounts)]
     pub struct AddLiquidity<'info> {
@@ -15,11 +18,19 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
         // Pool state account
         #[account(
             mut,
-            seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref()],
+            seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
             bump = pool.bump
         )]
         pub pool: Account<'info, Pool>,
 
+        // Global config, checked for the pause flag
+        #[account(
+            seeds = [Config::SEED],
+            bump = config.bump,
+            constraint = !config.paused @ ErrorCode::ProgramPaused
+        )]
+        pub config: Account<'info, Config>,
+
         // Pool authority PDA
         /// CHECK: Authority PDA, seeds checked below.
         #[account(
@@ -28,6 +39,13 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
         )]
         pub pool_authority: AccountInfo<'info>,
 
+        // Pool token mints, owned by SPL Token or Token-2022
+        #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+        pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+        #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+        pub token_b_mint: InterfaceAccount<'info, Mint>,
+
         // Pool's token accounts
         #[account(
             mut,
@@ -35,7 +53,7 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
             bump = pool.token_a_bump,
             constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
         )]
-        pub token_a_account: Account<'info, TokenAccount>,
+        pub token_a_account: InterfaceAccount<'info, TokenAccount>,
 
         #[account(
             mut,
@@ -43,22 +61,33 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
             bump = pool.token_b_bump,
             constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
         )]
-        pub token_b_account: Account<'info, TokenAccount>,
+        pub token_b_account: InterfaceAccount<'info, TokenAccount>,
 
-        // User's token accounts
+        // User's token accounts.
+        // Either can be left out on a native SOL side to pay with / receive lamports instead.
         #[account(
             mut,
             constraint = user_token_a.mint == pool.token_a_mint @ ErrorCode::InvalidMint,
             constraint = user_token_a.owner == user.key() @ ErrorCode::InvalidOwner
         )]
-        pub user_token_a: Account<'info, TokenAccount>,
+        pub user_token_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
         #[account(
             mut,
             constraint = user_token_b.mint == pool.token_b_mint @ ErrorCode::InvalidMint,
             constraint = user_token_b.owner == user.key() @ ErrorCode::InvalidOwner
         )]
-        pub user_token_b: Account<'info, TokenAccount>,
+        pub user_token_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+        // Temporary wSOL account standing in for the missing user token account, created and
+        // closed by the instruction (see `native_sol`)
+        /// CHECK: Uninitialized PDA, seeds checked below. Created as a token account in the handler.
+        #[account(
+            mut,
+            seeds = [WSOL_SEED, user.key().as_ref()],
+            bump
+        )]
+        pub user_wsol_account: Option<UncheckedAccount<'info>>,
 
         // LP token mint
         #[account(
@@ -67,7 +96,7 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
             bump = pool.lp_mint_bump,
             constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
         )]
-        pub lp_mint: Account<'info, Mint>,
+        pub lp_mint: InterfaceAccount<'info, Mint>,
 
         // User's LP token account (might need to be created if it doesn't exist)
         #[account(
@@ -76,11 +105,21 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
             associated_token::mint = lp_mint,
             associated_token::authority = user
         )]
-        pub user_lp_token_account: Account<'info, TokenAccount>, // Changed name for clarity
+        pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>, // Changed name for clarity
+
+        // Pool-owned LP account receiving the liquidity locked by the first deposit
+        #[account(
+            mut,
+            seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+            bump = pool.locked_lp_bump
+        )]
+        pub locked_lp_account: InterfaceAccount<'info, TokenAccount>,
 
         // System programs
         pub system_program: Program<'info, System>,
-        pub token_program: Program<'info, Token>,
+        pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+        pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+        pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
         pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>, // Needed for init_if_needed
         pub rent: Sysvar<'info, Rent>,
     }
@@ -94,26 +133,217 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
         amount_b_min: u64, // Minimum amount of token B user is willing to deposit
     ) -> Result<()> {
         msg!("Adding liquidity...");
-        let pool = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
+        let (amount_a, amount_b, liquidity_minted) = LocalDeposit {
+            user: &ctx.accounts.user,
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            authority_bump: ctx.bumps.pool_authority,
+            token_a_mint: &ctx.accounts.token_a_mint,
+            token_b_mint: &ctx.accounts.token_b_mint,
+            token_a_account: ctx.accounts.token_a_account.to_account_info(),
+            token_b_account: ctx.accounts.token_b_account.to_account_info(),
+            user_token_a: ctx.accounts.user_token_a.as_ref(),
+            user_token_b: ctx.accounts.user_token_b.as_ref(),
+            user_wsol_account: ctx.accounts.user_wsol_account.as_ref(),
+            wsol_bump: ctx.bumps.user_wsol_account,
+            lp_mint: &ctx.accounts.lp_mint,
+            user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
+            locked_lp_account: ctx.accounts.locked_lp_account.to_account_info(),
+            system_program: &ctx.accounts.system_program,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_a_program: &ctx.accounts.token_a_program,
+            token_b_program: &ctx.accounts.token_b_program,
+        }
+        .execute(amount_a_desired, amount_b_desired, amount_a_min, amount_b_min)?;
 
-        // Check if pool is active
-        require!(pool.status == 0, ErrorCode::PoolPaused); // Assuming 0 is Active
+        msg!("Liquidity added: A={}, B={}, LP={}", amount_a, amount_b, liquidity_minted);
+
+        Ok(())
+    }
+
+    // Accounts a deposit from a Solana user executes against, shared by `add_liquidity` and
+    // `bridge_add_liquidity`
+    pub struct LocalDeposit<'a, 'info> {
+        pub user: &'a Signer<'info>,
+        pub pool: &'a mut Account<'info, Pool>,
+        pub pool_authority: AccountInfo<'info>,
+        pub authority_bump: u8,
+        pub token_a_mint: &'a InterfaceAccount<'info, Mint>,
+        pub token_b_mint: &'a InterfaceAccount<'info, Mint>,
+        pub token_a_account: AccountInfo<'info>,
+        pub token_b_account: AccountInfo<'info>,
+        pub user_token_a: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        pub user_token_b: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        pub user_wsol_account: Option<&'a UncheckedAccount<'info>>,
+        pub wsol_bump: Option<u8>,
+        pub lp_mint: &'a InterfaceAccount<'info, Mint>,
+        pub user_lp_token_account: AccountInfo<'info>,
+        pub locked_lp_account: AccountInfo<'info>,
+        pub system_program: &'a Program<'info, System>,
+        pub token_program: AccountInfo<'info>,                 // LP mint's program
+        pub token_a_program: &'a Interface<'info, TokenInterface>,
+        pub token_b_program: &'a Interface<'info, TokenInterface>,
+    }
 
-        let reserve_a = ctx.accounts.token_a_account.amount;
-        let reserve_b = ctx.accounts.token_b_account.amount;
-        let lp_supply = ctx.accounts.lp_mint.supply;
+    impl<'a, 'info> LocalDeposit<'a, 'info> {
+        // Take the deposit from the user and mint their LP tokens.
+        // Returns the amounts that reached the vaults and the LP tokens minted to the user.
+        pub fn execute(
+            self,
+            amount_a_desired: u64,
+            amount_b_desired: u64,
+            amount_a_min: u64,
+            amount_b_min: u64,
+        ) -> Result<(u64, u64, u64)> {
+            // Native SOL is wrapped from the user's lamports for the transfer
+            let user_token_a = UserTokenAccount::resolve(
+                self.user_token_a,
+                self.user_wsol_account,
+                self.wsol_bump,
+                self.token_a_mint,
+                self.user,
+                self.token_a_program,
+                self.system_program,
+            )?;
+            let user_token_b = UserTokenAccount::resolve(
+                self.user_token_b,
+                self.user_wsol_account,
+                self.wsol_bump,
+                self.token_b_mint,
+                self.user,
+                self.token_b_program,
+                self.system_program,
+            )?;
+            let pool = self.pool;
+            let clock = Clock::get()?;
 
+            // Check if pool is active
+            pool.require_active()?;
+
+            // Price accumulators are weighted with the reserves held up to now
+            pool.accrue_prices(clock.unix_timestamp)?;
+
+            let before = pool.snapshot();
+
+            // LP is priced against everything backing it, including liquidity held by the linked pool
+            let (reserve_a, reserve_b) = pool.combined_reserves()?;
+            let lp_supply = self.lp_mint.supply;
+            let curve = pool.curve(clock.unix_timestamp)?;
+
+            // Transfer fees are withheld on the way in, so the deposit (and the minimums) are quoted on
+            // what reaches the vaults and the user sends enough to cover the fee
+            let (amount_a_optimal, amount_b_optimal, liquidity_minted) = quote_deposit(
+                &curve,
+                amount_received(self.token_a_mint, amount_a_desired)?,
+                amount_received(self.token_b_mint, amount_b_desired)?,
+                amount_a_min,
+                amount_b_min,
+                reserve_a,
+                reserve_b,
+                lp_supply,
+            )?;
+
+            // Transfer tokens from user to pool
+            let amount_a_sent = amount_to_send(self.token_a_mint, amount_a_optimal)?;
+            user_token_a.open(amount_a_sent)?;
+            transfer_tokens(
+                self.token_a_program.to_account_info(),
+                user_token_a.account.clone(),
+                self.token_a_mint,
+                self.token_a_account,
+                self.user.to_account_info(),
+                amount_a_sent,
+            )?;
+            user_token_a.close()?;
+
+            let amount_b_sent = amount_to_send(self.token_b_mint, amount_b_optimal)?;
+            user_token_b.open(amount_b_sent)?;
+            transfer_tokens(
+                self.token_b_program.to_account_info(),
+                user_token_b.account.clone(),
+                self.token_b_mint,
+                self.token_b_account,
+                self.user.to_account_info(),
+                amount_b_sent,
+            )?;
+            user_token_b.close()?;
+
+            // Mint LP tokens to user
+            mint_lp_tokens(
+                self.token_program.clone(),
+                self.lp_mint.to_account_info(),
+                self.user_lp_token_account,
+                self.pool_authority.clone(),
+                pool.key(), // Pool key needed for PDA seeds
+                liquidity_minted,
+                self.authority_bump,
+            )?;
+
+            let locked = locked_liquidity(lp_supply);
+            if locked > 0 {
+                mint_lp_tokens(
+                    self.token_program,
+                    self.lp_mint.to_account_info(),
+                    self.locked_lp_account,
+                    self.pool_authority,
+                    pool.key(),
+                    locked,
+                    self.authority_bump,
+                )?;
+            }
+
+            // Update pool state
+            pool.deposit_reserves(amount_a_optimal, amount_b_optimal)?;
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_add(liquidity_minted)
+                .and_then(|total| total.checked_add(locked))
+                .ok_or(ErrorCode::Overflow)?;
+            pool.last_updated_at = clock.unix_timestamp;
+
+            emit!(LiquidityAdded {
+                pool: pool.key(),
+                user: self.user.key(),
+                source_chain: wormhole::CHAIN_ID_SOLANA,
+                amount_a: amount_a_optimal,
+                amount_b: amount_b_optimal,
+                lp_minted: liquidity_minted,
+                before,
+                after: pool.snapshot(),
+                timestamp: clock.unix_timestamp,
+            });
+
+            Ok((amount_a_optimal, amount_b_optimal, liquidity_minted))
+        }
+    }
+
+    // Deposit amounts and LP tokens to mint for a deposit against the given LP reserves.
+    // Shared by `add_liquidity`, `bridge_add_liquidity` and remote deposits in `process_vaa`,
+    // which all pass the combined (local + remote) reserves.
+    // Only the first deposit depends on the curve. Later ones are taken at the reserve ratio on
+    // both curves: a balanced deposit leaves the price where it is, so it owes no fee and mints its
+    // exact share of the pool, the same as Curve's add_liquidity for a deposit in proportion.
+    #[allow(clippy::too_many_arguments)]
+    pub fn quote_deposit(
+        curve: &Curve,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<(u64, u64, u64)> {
         let (amount_a_optimal, amount_b_optimal) = if lp_supply == 0 {
             // First liquidity provider
             (amount_a_desired, amount_b_desired)
         } else {
             // Calculate optimal amounts based on current reserves
-            let amount_b_optimal_calc = amount_a_desired.checked_mul(reserve_b).unwrap().checked_div(reserve_a).unwrap();
+            let amount_b_optimal_calc = mul_div_floor(amount_a_desired, reserve_b, reserve_a)?;
             if amount_b_optimal_calc <= amount_b_desired {
                 (amount_a_desired, amount_b_optimal_calc)
             } else {
-                let amount_a_optimal_calc = amount_b_desired.checked_mul(reserve_a).unwrap().checked_div(reserve_b).unwrap();
+                let amount_a_optimal_calc = mul_div_floor(amount_b_desired, reserve_a, reserve_b)?;
                 (amount_a_optimal_calc, amount_b_desired)
             }
         };
@@ -124,74 +354,48 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
 
         // Calculate LP tokens to mint
         let liquidity_minted = if lp_supply == 0 {
-            // Use geometric mean for the first provider, or a simpler approach like 100 units
-            // Let's use a simpler fixed amount for the first liquidity to avoid sqrt complexity or large numbers
-            // Or base it on one of the amounts, e.g., amount_a_optimal
-            amount_a_optimal // Simplification: mint LP tokens equal to the amount of token A deposited initially
-                             // A more common approach is sqrt(amount_a * amount_b), but requires U128 or similar
+            // Geometric mean (StableSwap: the invariant D) for the first provider, minus the liquidity
+            // locked in the pool forever (see `locked_liquidity`); deposits too small to cover it are rejected
+            curve
+                .initial_liquidity(amount_a_optimal, amount_b_optimal)?
+                .checked_sub(Pool::MINIMUM_LIQUIDITY)
+                .ok_or(ErrorCode::InsufficientInitialLiquidity)?
         } else {
             // Calculate based on the ratio of deposit to reserves
             std::cmp::min(
-                amount_a_optimal.checked_mul(lp_supply).unwrap().checked_div(reserve_a).unwrap(),
-                amount_b_optimal.checked_mul(lp_supply).unwrap().checked_div(reserve_b).unwrap(),
+                mul_div_floor(amount_a_optimal, lp_supply, reserve_a)?,
+                mul_div_floor(amount_b_optimal, lp_supply, reserve_b)?,
             )
         };
 
         require!(liquidity_minted > 0, ErrorCode::ZeroLiquidityMinted);
 
-        // Transfer tokens from user to pool
-        transfer_tokens(
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.user_token_a.to_account_info(),
-            ctx.accounts.token_a_account.to_account_info(),
-            ctx.accounts.user.to_account_info(),
-            amount_a_optimal,
-        )?;
-
-        transfer_tokens(
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.user_token_b.to_account_info(),
-            ctx.accounts.token_b_account.to_account_info(),
-            ctx.accounts.user.to_account_info(),
-            amount_b_optimal,
-        )?;
-
-        // Mint LP tokens to user
-        mint_lp_tokens(
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.lp_mint.to_account_info(),
-            ctx.accounts.user_lp_token_account.to_account_info(),
-            ctx.accounts.pool_authority.to_account_info(),
-            pool.key(), // Pool key needed for PDA seeds
-            liquidity_minted,
-            ctx.bumps.pool_authority, // Access bump directly
-        )?;
-
-        // Update pool state (reload amounts after transfer)
-        pool.total_liquidity = pool.total_liquidity.checked_add(liquidity_minted).unwrap();
-        pool.last_updated_at = clock.unix_timestamp;
-
-        msg!("Liquidity added: A={}, B={}, LP={}", amount_a_optimal, amount_b_optimal, liquidity_minted);
+        Ok((amount_a_optimal, amount_b_optimal, liquidity_minted))
+    }
 
-        Ok(())
+    // LP tokens the first deposit mints to the pool's locked LP account on top of the depositor's
+    pub fn locked_liquidity(lp_supply: u64) -> u64 {
+        if lp_supply == 0 { Pool::MINIMUM_LIQUIDITY } else { 0 }
     }
 
-    // Helper function for token transfers
+    // Helper function for token transfers (SPL Token or Token-2022, the mint's program)
     pub fn transfer_tokens<'info>( // Make helper public
         token_program: AccountInfo<'info>,
         source: AccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
         destination: AccountInfo<'info>,
         authority: AccountInfo<'info>,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: source,
+            mint: mint.to_account_info(),
             to: destination,
-            authority: authority,
+            authority,
         };
         let cpi_program = token_program;
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
         Ok(())
     }
 
@@ -209,12 +413,12 @@ use crate::errors::ErrorCode; // Assuming you'll create an errors.rs file later
         let signer_seeds = &[&seeds[..]];
 
         let cpi_accounts = MintTo {
-            mint: mint,
+            mint,
             to: destination,
-            authority: authority,
+            authority,
         };
         let cpi_program = token_program;
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::mint_to(cpi_ctx, amount)?;
         Ok(())
     }
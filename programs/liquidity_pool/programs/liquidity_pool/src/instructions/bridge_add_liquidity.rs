@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{Config, Pool};
+use crate::errors::ErrorCode;
+use crate::instructions::add_liquidity::LocalDeposit;
+use crate::messaging::OutboundMessage;
+use crate::native_sol::WSOL_SEED;
+use crate::payloads::{AddLiquidityPayload, BridgePayload};
+
+#[derive(Accounts)]
+pub struct BridgeAddLiquidity<'info> {
+    // User adding liquidity
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool authority PDA
+    /// CHECK: Authority PDA, seeds checked below.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump // Bump needed for signing the LP mint
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    // Pool token mints, owned by SPL Token or Token-2022
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Pool's token accounts
+    #[account(
+        mut,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump = pool.token_a_bump,
+        constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump = pool.token_b_bump,
+        constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    // User's token accounts.
+    // Either can be left out on a native SOL side to pay with lamports instead.
+    #[account(
+        mut,
+        constraint = user_token_a.mint == pool.token_a_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_a.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_a: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_b.mint == pool.token_b_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_b.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Temporary wSOL account standing in for the missing user token account, created and
+    // closed by the instruction (see `native_sol`)
+    /// CHECK: Uninitialized PDA, seeds checked below. Created as a token account in the handler.
+    #[account(
+        mut,
+        seeds = [WSOL_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_wsol_account: Option<UncheckedAccount<'info>>,
+
+    // LP token mint
+    #[account(
+        mut,
+        seeds = [b"lp_mint".as_ref(), pool.key().as_ref()],
+        bump = pool.lp_mint_bump,
+        constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    // User's LP token account (might need to be created if it doesn't exist)
+    #[account(
+        init_if_needed, // Creates the account if it doesn't exist
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user
+    )]
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool-owned LP account receiving the liquidity locked by the first deposit
+    #[account(
+        mut,
+        seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+        bump = pool.locked_lp_bump
+    )]
+    pub locked_lp_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Wormhole core bridge accounts used to publish the deposit to Sui
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::BridgeData::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_bridge: Account<'info, wormhole::BridgeData>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::FeeCollector::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
+
+    /// CHECK: Emitter PDA of the pool, only used to sign the message.
+    #[account(
+        seeds = [wormhole::SEED_PREFIX_EMITTER, pool.key().as_ref()],
+        bump
+    )]
+    pub wormhole_emitter: AccountInfo<'info>,
+
+    /// CHECK: Sequence tracker of our emitter, created and incremented by the core bridge.
+    #[account(
+        mut,
+        seeds = [wormhole::SequenceTracker::SEED_PREFIX, wormhole_emitter.key().as_ref()],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_sequence: AccountInfo<'info>,
+
+    // Fresh keypair the core bridge writes the message into
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    // System programs
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>, // Needed for init_if_needed
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Handler function for depositing on Solana and mirroring the deposit into the Sui pool.
+// The deposit is taken exactly like `add_liquidity` (see `LocalDeposit`); the published message
+// carries the amounts so the Sui side can credit the same liquidity.
+pub fn handler(
+    ctx: Context<BridgeAddLiquidity>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64, // Minimum amount of token A user is willing to deposit
+    amount_b_min: u64, // Minimum amount of token B user is willing to deposit
+) -> Result<()> {
+    msg!("Adding liquidity (bridged)...");
+    // The deposit is mirrored into the linked pool
+    ctx.accounts.pool.require_linked()?;
+
+    // Quoted net of transfer fees, so Sui is told what reached the vaults
+    let (amount_a, amount_b, liquidity_minted) = LocalDeposit {
+        user: &ctx.accounts.user,
+        pool: &mut ctx.accounts.pool,
+        pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        authority_bump: ctx.bumps.pool_authority,
+        token_a_mint: &ctx.accounts.token_a_mint,
+        token_b_mint: &ctx.accounts.token_b_mint,
+        token_a_account: ctx.accounts.token_a_account.to_account_info(),
+        token_b_account: ctx.accounts.token_b_account.to_account_info(),
+        user_token_a: ctx.accounts.user_token_a.as_ref(),
+        user_token_b: ctx.accounts.user_token_b.as_ref(),
+        user_wsol_account: ctx.accounts.user_wsol_account.as_ref(),
+        wsol_bump: ctx.bumps.user_wsol_account,
+        lp_mint: &ctx.accounts.lp_mint,
+        user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
+        locked_lp_account: ctx.accounts.locked_lp_account.to_account_info(),
+        system_program: &ctx.accounts.system_program,
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_a_program: &ctx.accounts.token_a_program,
+        token_b_program: &ctx.accounts.token_b_program,
+    }
+    .execute(amount_a_desired, amount_b_desired, amount_a_min, amount_b_min)?;
+
+    let pool = &ctx.accounts.pool;
+    // Sui mirrors the deposit into its reserves
+    let payload = BridgePayload::AddLiquidity(AddLiquidityPayload {
+        pool_id: pool.pool_id,
+        amount_a,
+        amount_b,
+        recipient_address: None,
+    });
+
+    let pool_address = pool.key().to_bytes();
+    let linked_chain_id = pool.linked_chain_id;
+    let bridge_fee = ctx.accounts.wormhole_bridge.fee();
+    OutboundMessage {
+        wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
+        wormhole_bridge: ctx.accounts.wormhole_bridge.to_account_info(),
+        wormhole_fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
+        wormhole_emitter: ctx.accounts.wormhole_emitter.to_account_info(),
+        wormhole_sequence: ctx.accounts.wormhole_sequence.to_account_info(),
+        wormhole_message: ctx.accounts.wormhole_message.to_account_info(),
+        payer: ctx.accounts.user.to_account_info(),
+        clock: ctx.accounts.clock.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .post(bridge_fee, ctx.bumps.wormhole_emitter, pool_address, linked_chain_id, payload)?;
+
+    msg!("Liquidity added (bridged): A={}, B={}, LP={}", amount_a, amount_b, liquidity_minted);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::state::{BridgeRequest, BridgeStatus, Config};
+use crate::errors::ErrorCode;
+use crate::events::BridgeRequestStatusChanged;
+
+#[derive(Accounts)]
+pub struct CancelBridgeRequest<'info> {
+    // Program admin
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            BridgeRequest::SEED,
+            &bridge_request.emitter_chain.to_le_bytes()[..],
+            bridge_request.emitter_address.as_ref(),
+            &bridge_request.wormhole_sequence.to_le_bytes()[..]
+        ],
+        bump = bridge_request.bump,
+        constraint = bridge_request.status == BridgeStatus::Delayed @ ErrorCode::BridgeRequestNotDelayed
+    )]
+    pub bridge_request: Account<'info, BridgeRequest>,
+}
+
+// Handler function for dropping a delayed withdrawal (e.g. one sent by a compromised emitter).
+// The record stays, so the VAA can't be redeemed again.
+pub fn handler(ctx: Context<CancelBridgeRequest>) -> Result<()> {
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    bridge_request.status = BridgeStatus::Cancelled;
+    emit!(BridgeRequestStatusChanged::new(
+        bridge_request.key(),
+        bridge_request,
+        BridgeStatus::Delayed,
+        Clock::get()?.unix_timestamp,
+    ));
+
+    msg!(
+        "Cancelled bridge request: chain={}, sequence={}",
+        bridge_request.emitter_chain,
+        bridge_request.wormhole_sequence
+    );
+
+    Ok(())
+}
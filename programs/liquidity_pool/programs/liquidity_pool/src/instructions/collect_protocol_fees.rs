@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Config, Pool};
+use crate::errors::ErrorCode;
+use crate::events::FeesCollected;
+use crate::instructions::remove_liquidity::transfer_pool_tokens;
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    // Anyone can trigger collection, funds always go to the pool's fee recipient
+    pub collector: Signer<'info>,
+
+    // Pool state account
+    #[account(
+        mut, // Protocol fee counters are reset
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool authority PDA
+    /// CHECK: Authority PDA, seeds checked below. Used as signer.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    // Pool token mints, owned by SPL Token or Token-2022
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    // Pool's token accounts (fees are held here until collected)
+    #[account(
+        mut,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump = pool.token_a_bump,
+        constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump = pool.token_b_bump,
+        constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Fee recipient's token accounts
+    #[account(
+        mut,
+        constraint = recipient_token_a.mint == pool.token_a_mint @ ErrorCode::InvalidMint,
+        constraint = recipient_token_a.owner == pool.fee_recipient @ ErrorCode::InvalidOwner
+    )]
+    pub recipient_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_b.mint == pool.token_b_mint @ ErrorCode::InvalidMint,
+        constraint = recipient_token_b.owner == pool.fee_recipient @ ErrorCode::InvalidOwner
+    )]
+    pub recipient_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+}
+
+// Handler function for sending accrued protocol fees to the fee recipient
+pub fn handler(ctx: Context<CollectProtocolFees>) -> Result<()> {
+    msg!("Collecting protocol fees...");
+    let pool = &mut ctx.accounts.pool;
+    let clock = Clock::get()?;
+
+    let amount_a = pool.protocol_fee_a;
+    let amount_b = pool.protocol_fee_b;
+
+    if amount_a > 0 {
+        transfer_pool_tokens(
+            ctx.accounts.token_a_program.to_account_info(),
+            ctx.accounts.token_a_account.to_account_info(),
+            &ctx.accounts.token_a_mint,
+            ctx.accounts.recipient_token_a.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+            pool.key(),
+            amount_a,
+            ctx.bumps.pool_authority,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_pool_tokens(
+            ctx.accounts.token_b_program.to_account_info(),
+            ctx.accounts.token_b_account.to_account_info(),
+            &ctx.accounts.token_b_mint,
+            ctx.accounts.recipient_token_b.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+            pool.key(),
+            amount_b,
+            ctx.bumps.pool_authority,
+        )?;
+    }
+
+    // Reset counters
+    pool.protocol_fee_a = 0;
+    pool.protocol_fee_b = 0;
+    pool.last_updated_at = clock.unix_timestamp;
+
+    // LP reserves are unaffected, only the fee counters are cleared
+    emit!(FeesCollected {
+        pool: pool.key(),
+        recipient: pool.fee_recipient,
+        amount_a,
+        amount_b,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Protocol fees collected: A={}, B={} to {}", amount_a, amount_b, pool.fee_recipient);
+
+    Ok(())
+}
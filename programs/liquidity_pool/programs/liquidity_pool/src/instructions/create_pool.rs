@@ -1,20 +1,33 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use crate::state::Pool; // Import the Pool state
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Config, CurveType, Pool, PoolStatus}; // Import the Pool and Config state
+use crate::curve::StableSwap;
+use crate::errors::ErrorCode;
+use crate::events::PoolCreated;
+use crate::token_extensions::require_supported_mint;
 
 #[derive(Accounts)]
-#[instruction(fee_percentage: u64, pool_id: [u8; 32])] // Define instruction arguments used in seeds/constraints
+#[instruction(fee_percentage: u64)] // Define instruction arguments used in seeds/constraints
 pub struct CreatePool<'info> {
     // Payer of the transaction and initial pool creator
     #[account(mut)]
     pub creator: Signer<'info>,
 
-    // Pool state account (PDA) - needs to be initialized
+    // Global config, provides allowed fee tiers and protocol fee defaults
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool state account (PDA) - needs to be initialized.
+    // One pool per pair and fee tier: the mints are sorted (see `token_b_mint`) and the fee is part of the seeds.
     #[account(
         init,
         payer = creator,
         space = Pool::SIZE, // Use the calculated size from state/pool.rs
-        seeds = [b"pool".as_ref(), token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        seeds = [b"pool".as_ref(), token_a_mint.key().as_ref(), token_b_mint.key().as_ref(), &fee_percentage.to_le_bytes()[..]],
         bump
     )]
     pub pool: Account<'info, Pool>,
@@ -27,9 +40,15 @@ pub struct CreatePool<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    // Token mints
-    pub token_a_mint: Account<'info, Mint>,
-    pub token_b_mint: Account<'info, Mint>,
+    // Token mints, SPL Token or Token-2022 (extensions are checked in the handler).
+    // Passed in canonical order so a pair can't get a second pool with the mints swapped.
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mint::token_program = token_b_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ ErrorCode::MintsNotSorted
+    )]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
 
     // LP token mint (PDA) - needs to be initialized
     #[account(
@@ -40,7 +59,7 @@ pub struct CreatePool<'info> {
         seeds = [b"lp_mint".as_ref(), pool.key().as_ref()],
         bump
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
 
     // Token accounts owned by the pool authority (PDAs) - need to be initialized
     #[account(
@@ -48,34 +67,71 @@ pub struct CreatePool<'info> {
         payer = creator,
         token::mint = token_a_mint,
         token::authority = pool_authority,
+        token::token_program = token_a_program,
         seeds = [b"token_a".as_ref(), pool.key().as_ref()],
         bump
     )]
-    pub token_a_account: Account<'info, TokenAccount>,
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init,
         payer = creator,
         token::mint = token_b_mint,
         token::authority = pool_authority,
+        token::token_program = token_b_program,
         seeds = [b"token_b".as_ref(), pool.key().as_ref()],
         bump
     )]
-    pub token_b_account: Account<'info, TokenAccount>,
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    // LP token account owned by the pool authority; holds the liquidity locked by the first deposit
+    #[account(
+        init,
+        payer = creator,
+        token::mint = lp_mint,
+        token::authority = pool_authority,
+        seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub locked_lp_account: InterfaceAccount<'info, TokenAccount>,
 
     // System programs
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
     pub rent: Sysvar<'info, Rent>,
 }
 
 // Instruction handler function
-pub fn handler(ctx: Context<CreatePool>, fee_percentage: u64, pool_id: [u8; 32]) -> Result<()> {
+pub fn handler(
+    ctx: Context<CreatePool>,
+    fee_percentage: u64,
+    curve_type: CurveType,
+    amplification: u64,
+) -> Result<()> {
     msg!("Creating new liquidity pool...");
 
     // Get the accounts
+    let config = &ctx.accounts.config;
     let pool = &mut ctx.accounts.pool;
-    let _creator = &ctx.accounts.creator; // Mark as unused if not needed directly
+
+    // Only admin-approved fee tiers can be used
+    config.require_fee_tier(fee_percentage)?;
+
+    // Transfer hooks, non-transferable mints and the like can't be held by a pool
+    require_supported_mint(&ctx.accounts.token_a_mint.to_account_info())?;
+    require_supported_mint(&ctx.accounts.token_b_mint.to_account_info())?;
+
+    // Amplification only means something to StableSwap pools, which also need decimals that can
+    // be normalised to each other
+    let (decimals_a, decimals_b) = (ctx.accounts.token_a_mint.decimals, ctx.accounts.token_b_mint.decimals);
+    match curve_type {
+        CurveType::ConstantProduct => require!(amplification == 0, ErrorCode::InvalidAmplification),
+        CurveType::StableSwap => {
+            StableSwap::new(amplification, decimals_a, decimals_b)?;
+        }
+    }
 
     // Get bumps directly from the Bumps struct generated by #[derive(Accounts)]
     let _authority_bump = ctx.bumps.pool_authority; // Mark as unused
@@ -84,6 +140,9 @@ pub fn handler(ctx: Context<CreatePool>, fee_percentage: u64, pool_id: [u8; 32])
     let token_b_bump = ctx.bumps.token_b_account;
     let pool_bump = ctx.bumps.pool; // Bump for the pool account itself
 
+    // Derived rather than chosen, so Sui and Solana agree on it without coordinating
+    let pool_id = Pool::derive_pool_id(&ctx.accounts.token_a_mint.key(), &ctx.accounts.token_b_mint.key(), fee_percentage);
+
     // Initialize the pool state
     pool.authority = ctx.accounts.pool_authority.key();
     pool.token_a_mint = ctx.accounts.token_a_mint.key();
@@ -94,17 +153,39 @@ pub fn handler(ctx: Context<CreatePool>, fee_percentage: u64, pool_id: [u8; 32])
     pool.fee_percentage = fee_percentage;
     pool.total_liquidity = 0; // Initially no liquidity
     pool.pool_id = pool_id;
-    pool.status = 0; // 0: Active
+    pool.status = PoolStatus::Active;
     pool.last_updated_at = Clock::get()?.unix_timestamp;
     pool.protocol_fee_a = 0;
     pool.protocol_fee_b = 0;
+    pool.protocol_fee_share = config.default_protocol_fee_share;
+    pool.fee_recipient = config.admin; // The admin receives protocol fees until reconfigured
     pool.bump = pool_bump; // Store the bump for the main pool account PDA
     pool.lp_mint_bump = lp_mint_bump;
     pool.token_a_bump = token_a_bump;
     pool.token_b_bump = token_b_bump;
+    pool.linked_chain_id = 0; // Not linked to a remote pool yet
+    pool.linked_address = [0u8; 32];
+    pool.remote_reserve_a = 0;
+    pool.remote_reserve_b = 0;
+    pool.price_a_cumulative = 0;
+    pool.price_b_cumulative = 0;
+    pool.price_updated_at = pool.last_updated_at;
+    pool.reserve_a = 0;
+    pool.reserve_b = 0;
+    pool.locked_lp_bump = ctx.bumps.locked_lp_account;
+    pool.curve_type = curve_type;
+    pool.amp_initial = amplification;
+    pool.amp_target = amplification;
+    pool.amp_ramp_start = 0; // No ramp yet, one can start right away
+    pool.amp_ramp_end = 0;
+    pool.token_a_decimals = decimals_a;
+    pool.token_b_decimals = decimals_b;
+
+    emit!(PoolCreated::new(pool.key(), pool));
 
     msg!("Pool created successfully with ID: {:?}", pool_id);
     msg!("Pool account address: {}", pool.key());
+    msg!("Curve: {:?}, amplification: {}", pool.curve_type, pool.amp_target);
     msg!("Pool authority PDA: {}", pool.authority);
     msg!("LP Mint PDA: {}", pool.lp_mint);
 
@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{BridgeRequest, BridgeStatus, Config, CurveType, ForeignEmitter, Pool, PoolStatus};
+use crate::errors::ErrorCode;
+use crate::events::{PoolCreated, VaaProcessed};
+use crate::instructions::process_vaa::{decode_vaa_payload, record_completed};
+use crate::payloads::{BridgeOperationCode, BridgePayload, RawPayload};
+use crate::token_extensions::require_supported_mint;
+
+#[derive(Accounts)]
+#[instruction(vaa_hash: [u8; 32], fee_percentage: u64)]
+pub struct CreatePoolFromVaa<'info> {
+    // Anyone can relay the VAA and pays for the new accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Global config, provides allowed fee tiers and protocol fee defaults
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // Wormhole core bridge program
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    // Verified VAA, bound to `vaa_hash` (see ProcessVAA)
+    #[account(
+        seeds = [wormhole::SEED_PREFIX_POSTED_VAA, &vaa_hash],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub posted_vaa: Box<Account<'info, wormhole::PostedVaa<RawPayload>>>,
+
+    // Only a registered emitter (the remote factory) can create pools
+    #[account(
+        seeds = [ForeignEmitter::SEED, &posted_vaa.emitter_chain().to_le_bytes()[..]],
+        bump = foreign_emitter.bump,
+        constraint = foreign_emitter.chain == posted_vaa.emitter_chain() @ ErrorCode::InvalidEmitterChain,
+        constraint = foreign_emitter.verify(posted_vaa.emitter_address()) @ ErrorCode::InvalidEmitterAddress
+    )]
+    pub foreign_emitter: Box<Account<'info, ForeignEmitter>>,
+
+    // Replay protection, same record as ProcessVAA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BridgeRequest::size(posted_vaa.payload_size() as usize),
+        seeds = [
+            BridgeRequest::SEED,
+            &posted_vaa.emitter_chain().to_le_bytes()[..],
+            posted_vaa.emitter_address().as_ref(),
+            &posted_vaa.sequence().to_le_bytes()[..]
+        ],
+        bump
+    )]
+    pub bridge_request: Box<Account<'info, BridgeRequest>>,
+
+    // The pool and its accounts are derived exactly like in CreatePool
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::SIZE,
+        seeds = [b"pool".as_ref(), token_a_mint.key().as_ref(), token_b_mint.key().as_ref(), &fee_percentage.to_le_bytes()[..]],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: This is a PDA, seeds are checked below. No data read/written directly.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    // Token mints, checked against the payload and for unsupported extensions in the handler
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mint::token_program = token_b_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ ErrorCode::MintsNotSorted
+    )]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 6,
+        mint::authority = pool_authority,
+        seeds = [b"lp_mint".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = token_a_mint,
+        token::authority = pool_authority,
+        token::token_program = token_a_program,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = token_b_mint,
+        token::authority = pool_authority,
+        token::token_program = token_b_program,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = pool_authority,
+        seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub locked_lp_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // System programs
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Handler function for creating a pool requested by a remote factory.
+// The new pool is linked to the remote pool that sent the request.
+pub fn handler(ctx: Context<CreatePoolFromVaa>, _vaa_hash: [u8; 32], fee_percentage: u64) -> Result<()> {
+    msg!("Creating liquidity pool from VAA...");
+
+    // A freshly created record is Pending, anything else means this VAA was already redeemed
+    require!(
+        ctx.accounts.bridge_request.status == BridgeStatus::Pending,
+        ErrorCode::VaaAlreadyProcessed
+    );
+
+    let vaa = &ctx.accounts.posted_vaa;
+    let request = match decode_vaa_payload(vaa)?.payload {
+        BridgePayload::CreatePool(request) => request,
+        other => {
+            msg!("Expected CreatePool, got {:?}", other.op_code());
+            return err!(ErrorCode::InvalidBridgeOperation);
+        }
+    };
+    // The short form only carries the source pool, Solana needs the pool parameters too
+    let params = request.params.ok_or(ErrorCode::InvalidVaaPayload)?;
+    msg!("Payload: {:?}", request);
+
+    require!(
+        ctx.accounts.token_a_mint.key().to_bytes() == params.token_a_mint
+            && ctx.accounts.token_b_mint.key().to_bytes() == params.token_b_mint,
+        ErrorCode::InvalidMint
+    );
+    require!(fee_percentage == params.fee_percentage, ErrorCode::InvalidFeePercentage);
+    // The factory has to have derived the ID the same way, otherwise the two pools disagree on it
+    require!(
+        params.pool_id == Pool::derive_pool_id(&ctx.accounts.token_a_mint.key(), &ctx.accounts.token_b_mint.key(), fee_percentage),
+        ErrorCode::InvalidPoolId
+    );
+    require_supported_mint(&ctx.accounts.token_a_mint.to_account_info())?;
+    require_supported_mint(&ctx.accounts.token_b_mint.to_account_info())?;
+
+    // Only admin-approved fee tiers can be used
+    let config = &ctx.accounts.config;
+    config.require_fee_tier(params.fee_percentage)?;
+
+    // Initialize the pool state
+    let pool = &mut ctx.accounts.pool;
+    pool.authority = ctx.accounts.pool_authority.key();
+    pool.token_a_mint = ctx.accounts.token_a_mint.key();
+    pool.token_b_mint = ctx.accounts.token_b_mint.key();
+    pool.token_a_account = ctx.accounts.token_a_account.key();
+    pool.token_b_account = ctx.accounts.token_b_account.key();
+    pool.lp_mint = ctx.accounts.lp_mint.key();
+    pool.fee_percentage = params.fee_percentage;
+    pool.total_liquidity = 0;
+    pool.pool_id = params.pool_id;
+    pool.status = PoolStatus::Active;
+    pool.last_updated_at = Clock::get()?.unix_timestamp;
+    pool.protocol_fee_a = 0;
+    pool.protocol_fee_b = 0;
+    pool.protocol_fee_share = config.default_protocol_fee_share;
+    pool.fee_recipient = config.admin;
+    pool.bump = ctx.bumps.pool;
+    pool.lp_mint_bump = ctx.bumps.lp_mint;
+    pool.token_a_bump = ctx.bumps.token_a_account;
+    pool.token_b_bump = ctx.bumps.token_b_account;
+    pool.linked_chain_id = vaa.emitter_chain();
+    pool.linked_address = request.source_link_address;
+    pool.remote_reserve_a = 0;
+    pool.remote_reserve_b = 0;
+    pool.price_a_cumulative = 0;
+    pool.price_b_cumulative = 0;
+    pool.price_updated_at = pool.last_updated_at;
+    pool.reserve_a = 0;
+    pool.reserve_b = 0;
+    pool.locked_lp_bump = ctx.bumps.locked_lp_account;
+    // The factory message carries no curve, pools created by it are constant-product like their Sui counterparts
+    pool.curve_type = CurveType::ConstantProduct;
+    pool.amp_initial = 0;
+    pool.amp_target = 0;
+    pool.amp_ramp_start = 0;
+    pool.amp_ramp_end = 0;
+    pool.token_a_decimals = ctx.accounts.token_a_mint.decimals;
+    pool.token_b_decimals = ctx.accounts.token_b_mint.decimals;
+
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    record_completed(bridge_request, vaa, ctx.bumps.bridge_request)?;
+    bridge_request.pool = pool.key();
+
+    emit!(PoolCreated::new(pool.key(), pool));
+    emit!(VaaProcessed::new(bridge_request, BridgeOperationCode::CreatePool as u8));
+
+    msg!("Pool created from VAA with ID: {:?}", params.pool_id);
+    msg!("Pool account address: {}", pool.key());
+    msg!("Linked to chain {} pool {}", pool.linked_chain_id, hex::encode(pool.linked_address));
+
+    Ok(())
+}
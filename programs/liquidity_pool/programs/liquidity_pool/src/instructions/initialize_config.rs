@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, Pool};
+use crate::errors::ErrorCode;
+use crate::program::LiquidityPoolProgram;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // Program upgrade authority, becomes the initial admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // Global config account (PDA) - needs to be initialized
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SIZE,
+        seeds = [Config::SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // This program, used to locate its program data account
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, LiquidityPoolProgram>,
+
+    // Only the upgrade authority may initialize config, preventing front-running after deploy
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::InvalidAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Handler function for creating the global config
+pub fn handler(
+    ctx: Context<InitializeConfig>,
+    fee_tiers: Vec<u64>,
+    default_protocol_fee_share: u64,
+) -> Result<()> {
+    msg!("Initializing program config...");
+    Config::validate_fee_tiers(&fee_tiers)?;
+    require!(default_protocol_fee_share <= Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.fee_tiers = fee_tiers;
+    config.default_protocol_fee_share = default_protocol_fee_share;
+    config.paused = false;
+    config.bump = ctx.bumps.config;
+
+    msg!("Config initialized: admin={}, fee_tiers={:?}", config.admin, config.fee_tiers);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::{Oracle, Pool};
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(capacity: u16)]
+pub struct InitializeOracle<'info> {
+    // Anyone can pay for a pool's oracle
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut, // Price accumulators are brought up to date for the first observation
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Observation ring buffer (PDA per pool)
+    #[account(
+        init,
+        payer = payer,
+        space = Oracle::size(capacity),
+        seeds = [Oracle::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub oracle: Account<'info, Oracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Handler function for creating a pool's observation ring buffer
+pub fn handler(ctx: Context<InitializeOracle>, capacity: u16) -> Result<()> {
+    require!(
+        (1..=Oracle::MAX_CAPACITY).contains(&capacity),
+        ErrorCode::InvalidOracleCapacity
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    let observation = pool.accrue_prices(Clock::get()?.unix_timestamp)?;
+    // From now on every swap has to record into the oracle
+    pool.has_oracle = true;
+
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.pool = ctx.accounts.pool.key();
+    oracle.capacity = capacity;
+    oracle.bump = ctx.bumps.oracle;
+    oracle.write(observation);
+
+    msg!("Oracle initialized: pool={}, capacity={}", oracle.pool, capacity);
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{Config, ForeignEmitter, Pool};
+use crate::errors::ErrorCode;
+use crate::events::PoolLinked;
+use crate::instructions::register_emitter::validate_emitter;
+use crate::messaging::OutboundMessage;
+use crate::payloads::{BridgePayload, LinkPoolPayload};
+
+#[derive(Accounts)]
+#[instruction(remote_chain: u16)]
+pub struct LinkPool<'info> {
+    // Program admin, also pays the Wormhole fee
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Pools can only be linked to chains with a registered emitter
+    #[account(
+        seeds = [ForeignEmitter::SEED, &remote_chain.to_le_bytes()[..]],
+        bump = foreign_emitter.bump
+    )]
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+
+    // Wormhole core bridge accounts used to publish the link
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::BridgeData::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_bridge: Account<'info, wormhole::BridgeData>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::FeeCollector::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
+
+    /// CHECK: Emitter PDA of the pool, only used to sign the message.
+    #[account(
+        seeds = [wormhole::SEED_PREFIX_EMITTER, pool.key().as_ref()],
+        bump
+    )]
+    pub wormhole_emitter: AccountInfo<'info>,
+
+    /// CHECK: Sequence tracker of our emitter, created and incremented by the core bridge.
+    #[account(
+        mut,
+        seeds = [wormhole::SequenceTracker::SEED_PREFIX, wormhole_emitter.key().as_ref()],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_sequence: AccountInfo<'info>,
+
+    // Fresh keypair the core bridge writes the message into
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Handler function for linking a pool to its counterpart on another chain.
+// Publishes LINK_POOL with this pool's emitter address so the remote side can link back;
+// the remote pool checks the emitter of every message it receives against it.
+pub fn handler(ctx: Context<LinkPool>, remote_chain: u16, remote_address: [u8; 32]) -> Result<()> {
+    validate_emitter(remote_chain, &remote_address)?;
+
+    // The remote reserves are held by the current counterpart, a new link would orphan them
+    let pool = &mut ctx.accounts.pool;
+    require!(
+        pool.remote_reserve_a == 0 && pool.remote_reserve_b == 0,
+        ErrorCode::RemoteReservesOutstanding
+    );
+    let old_chain_id = pool.linked_chain_id;
+    let old_address = pool.linked_address;
+    pool.linked_chain_id = remote_chain;
+    pool.linked_address = remote_address;
+    pool.last_updated_at = Clock::get()?.unix_timestamp;
+
+    let pool_address = pool.key().to_bytes();
+    let emitter_address = ctx.accounts.wormhole_emitter.key().to_bytes();
+    let bridge_fee = ctx.accounts.wormhole_bridge.fee();
+    OutboundMessage {
+        wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
+        wormhole_bridge: ctx.accounts.wormhole_bridge.to_account_info(),
+        wormhole_fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
+        wormhole_emitter: ctx.accounts.wormhole_emitter.to_account_info(),
+        wormhole_sequence: ctx.accounts.wormhole_sequence.to_account_info(),
+        wormhole_message: ctx.accounts.wormhole_message.to_account_info(),
+        payer: ctx.accounts.admin.to_account_info(),
+        clock: ctx.accounts.clock.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .post(
+        bridge_fee,
+        ctx.bumps.wormhole_emitter,
+        pool_address,
+        remote_chain,
+        BridgePayload::LinkPool(LinkPoolPayload { pool_address: emitter_address }),
+    )?;
+
+    emit!(PoolLinked {
+        pool: ctx.accounts.pool.key(),
+        old_chain_id,
+        old_address,
+        linked_chain_id: remote_chain,
+        linked_address: remote_address,
+        emitter: ctx.accounts.wormhole_emitter.key(),
+        timestamp: ctx.accounts.pool.last_updated_at,
+    });
+
+    msg!("Pool {} linked to chain {} pool {}", ctx.accounts.pool.key(), remote_chain, hex::encode(remote_address));
+
+    Ok(())
+}
@@ -1,9 +1,126 @@
+// Every instruction module exports its own `handler`, so only the accounts structs (and the client
+// modules Anchor derives for them) are re-exported; callers reach handlers by their full path.
+
 pub mod create_pool;
 pub mod add_liquidity;
 pub mod remove_liquidity;
 pub mod process_vaa;
+pub mod swap;
+pub mod swap_exact_out;
+pub mod collect_protocol_fees;
+pub mod set_protocol_fee;
+pub mod initialize_config;
+pub mod update_config;
+pub mod set_pool_status;
+pub mod register_emitter;
+pub mod update_emitter;
+pub mod remove_emitter;
+pub mod bridge_add_liquidity;
+pub mod request_remove_liquidity_remote;
+pub mod create_pool_from_vaa;
+pub mod link_pool;
+pub mod set_pool_rate_limit;
+pub mod set_chain_rate_limit;
+pub mod release_bridge_request;
+pub mod cancel_bridge_request;
+pub mod retry_bridge_request;
+pub mod refund_bridge_request;
+pub mod initialize_oracle;
+pub mod record_observation;
+pub mod observe;
+pub mod ramp_amplification;
+pub mod stop_ramp_amplification;
+
+pub use create_pool::CreatePool;
+pub use add_liquidity::AddLiquidity;
+pub use remove_liquidity::RemoveLiquidity;
+pub use process_vaa::ProcessVAA;
+pub use swap::Swap;
+pub use collect_protocol_fees::CollectProtocolFees;
+pub use set_protocol_fee::SetProtocolFee;
+pub use initialize_config::InitializeConfig;
+pub use update_config::UpdateConfig;
+pub use set_pool_status::SetPoolStatus;
+pub use register_emitter::RegisterEmitter;
+pub use update_emitter::UpdateEmitter;
+pub use remove_emitter::RemoveEmitter;
+pub use bridge_add_liquidity::BridgeAddLiquidity;
+pub use request_remove_liquidity_remote::RequestRemoveLiquidityRemote;
+pub use create_pool_from_vaa::CreatePoolFromVaa;
+pub use link_pool::LinkPool;
+pub use set_pool_rate_limit::SetPoolRateLimit;
+pub use set_chain_rate_limit::SetChainRateLimit;
+pub use release_bridge_request::ReleaseBridgeRequest;
+pub use cancel_bridge_request::CancelBridgeRequest;
+pub use retry_bridge_request::RetryBridgeRequest;
+pub use refund_bridge_request::RefundBridgeRequest;
+pub use initialize_oracle::InitializeOracle;
+pub use record_observation::RecordObservation;
+pub use observe::Observe;
+pub use ramp_amplification::RampAmplification;
+pub use stop_ramp_amplification::StopRampAmplification;
+
+// Anchor's `#[program]` expands to paths into these crate-private client modules at the crate root.
+pub(crate) use create_pool::__client_accounts_create_pool;
+pub(crate) use add_liquidity::__client_accounts_add_liquidity;
+pub(crate) use remove_liquidity::__client_accounts_remove_liquidity;
+pub(crate) use process_vaa::__client_accounts_process_vaa;
+pub(crate) use swap::__client_accounts_swap;
+pub(crate) use collect_protocol_fees::__client_accounts_collect_protocol_fees;
+pub(crate) use set_protocol_fee::__client_accounts_set_protocol_fee;
+pub(crate) use initialize_config::__client_accounts_initialize_config;
+pub(crate) use update_config::__client_accounts_update_config;
+pub(crate) use set_pool_status::__client_accounts_set_pool_status;
+pub(crate) use register_emitter::__client_accounts_register_emitter;
+pub(crate) use update_emitter::__client_accounts_update_emitter;
+pub(crate) use remove_emitter::__client_accounts_remove_emitter;
+pub(crate) use bridge_add_liquidity::__client_accounts_bridge_add_liquidity;
+pub(crate) use request_remove_liquidity_remote::__client_accounts_request_remove_liquidity_remote;
+pub(crate) use create_pool_from_vaa::__client_accounts_create_pool_from_vaa;
+pub(crate) use link_pool::__client_accounts_link_pool;
+pub(crate) use set_pool_rate_limit::__client_accounts_set_pool_rate_limit;
+pub(crate) use set_chain_rate_limit::__client_accounts_set_chain_rate_limit;
+pub(crate) use release_bridge_request::__client_accounts_release_bridge_request;
+pub(crate) use cancel_bridge_request::__client_accounts_cancel_bridge_request;
+pub(crate) use retry_bridge_request::__client_accounts_retry_bridge_request;
+pub(crate) use refund_bridge_request::__client_accounts_refund_bridge_request;
+pub(crate) use initialize_oracle::__client_accounts_initialize_oracle;
+pub(crate) use record_observation::__client_accounts_record_observation;
+pub(crate) use observe::__client_accounts_observe;
+pub(crate) use ramp_amplification::__client_accounts_ramp_amplification;
+pub(crate) use stop_ramp_amplification::__client_accounts_stop_ramp_amplification;
+
+#[cfg(feature = "cpi")]
+pub(crate) use self::cpi_accounts::*;
 
-pub use create_pool::*;
-pub use add_liquidity::*;
-pub use remove_liquidity::*;
-pub use process_vaa::*;
+#[cfg(feature = "cpi")]
+mod cpi_accounts {
+    pub(crate) use super::create_pool::__cpi_client_accounts_create_pool;
+    pub(crate) use super::add_liquidity::__cpi_client_accounts_add_liquidity;
+    pub(crate) use super::remove_liquidity::__cpi_client_accounts_remove_liquidity;
+    pub(crate) use super::process_vaa::__cpi_client_accounts_process_vaa;
+    pub(crate) use super::swap::__cpi_client_accounts_swap;
+    pub(crate) use super::collect_protocol_fees::__cpi_client_accounts_collect_protocol_fees;
+    pub(crate) use super::set_protocol_fee::__cpi_client_accounts_set_protocol_fee;
+    pub(crate) use super::initialize_config::__cpi_client_accounts_initialize_config;
+    pub(crate) use super::update_config::__cpi_client_accounts_update_config;
+    pub(crate) use super::set_pool_status::__cpi_client_accounts_set_pool_status;
+    pub(crate) use super::register_emitter::__cpi_client_accounts_register_emitter;
+    pub(crate) use super::update_emitter::__cpi_client_accounts_update_emitter;
+    pub(crate) use super::remove_emitter::__cpi_client_accounts_remove_emitter;
+    pub(crate) use super::bridge_add_liquidity::__cpi_client_accounts_bridge_add_liquidity;
+    pub(crate) use super::request_remove_liquidity_remote::__cpi_client_accounts_request_remove_liquidity_remote;
+    pub(crate) use super::create_pool_from_vaa::__cpi_client_accounts_create_pool_from_vaa;
+    pub(crate) use super::link_pool::__cpi_client_accounts_link_pool;
+    pub(crate) use super::set_pool_rate_limit::__cpi_client_accounts_set_pool_rate_limit;
+    pub(crate) use super::set_chain_rate_limit::__cpi_client_accounts_set_chain_rate_limit;
+    pub(crate) use super::release_bridge_request::__cpi_client_accounts_release_bridge_request;
+    pub(crate) use super::cancel_bridge_request::__cpi_client_accounts_cancel_bridge_request;
+    pub(crate) use super::retry_bridge_request::__cpi_client_accounts_retry_bridge_request;
+    pub(crate) use super::refund_bridge_request::__cpi_client_accounts_refund_bridge_request;
+    pub(crate) use super::initialize_oracle::__cpi_client_accounts_initialize_oracle;
+    pub(crate) use super::record_observation::__cpi_client_accounts_record_observation;
+    pub(crate) use super::observe::__cpi_client_accounts_observe;
+    pub(crate) use super::ramp_amplification::__cpi_client_accounts_ramp_amplification;
+    pub(crate) use super::stop_ramp_amplification::__cpi_client_accounts_stop_ramp_amplification;
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::{Oracle, Pool, Twap};
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    #[account(
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [Oracle::SEED, pool.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, Oracle>,
+}
+
+// Handler function for reading TWAPs over the last `seconds_ago` seconds (read-only).
+// Windows ending now are extended past the newest observation with the pool's accumulators
+// and the current reserves, which are what the pool would accrue if updated now.
+pub fn handler(ctx: Context<Observe>, seconds_ago: Vec<u32>) -> Result<Vec<Twap>> {
+    let pool = &ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+    let updated = pool.price_observation();
+    let current = updated.accumulate(pool.spot_prices(now)?, now);
+
+    ctx.accounts.oracle.observe(&[updated, current], &seconds_ago)
+}
@@ -1,44 +1,75 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use crate::state::{Pool, BridgeRequest, BridgeStatus};
+use crate::state::{BridgeFailure, BridgeRequest, BridgeStatus, Config, ForeignEmitter, Pool, RateLimit};
 use crate::errors::ErrorCode;
-use crate::payloads::{AddLiquidityCompletionPayload, RemoveLiquidityCompletionPayload};
-use wormhole_anchor_sdk::wormhole; // Keep anchor sdk import for BridgeData etc.
-use wormhole_vaas::{Vaa, Readable, payloads::PayloadKind}; // Import Vaa, Readable, and PayloadKind
-use std::io::Cursor; // Import Cursor for reading from slice
-use borsh::BorshDeserialize; // Keep for our custom payload deserialization
-use crate::instructions::add_liquidity::mint_lp_tokens;
+use crate::events::{LiquidityAdded, LiquidityRemoved, VaaProcessed};
+use crate::payloads::{BridgePayload, PayloadEnvelope, RawPayload, RemoveLiquidityPayload, PROTOCOL_VERSION_1};
+use wormhole_anchor_sdk::wormhole;
+use crate::instructions::add_liquidity::{locked_liquidity, mint_lp_tokens, quote_deposit};
 use crate::instructions::remove_liquidity::transfer_pool_tokens;
 use hex; // Import hex for encoding
 
 #[derive(Accounts)]
+#[instruction(vaa_hash: [u8; 32])]
 pub struct ProcessVAA<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // Wormhole core bridge program
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    // Posted VAA account created by the core bridge's `post_vaa`, which only succeeds once the
+    // guardian signatures have been verified. `Account` checks the owner is the core bridge and
+    // the seeds bind the account to `vaa_hash`.
     #[account(
-        seeds = [b"Bridge".as_ref()],
+        seeds = [wormhole::SEED_PREFIX_POSTED_VAA, &vaa_hash],
         bump,
-        seeds::program = wormhole::program::ID // Assuming wormhole program ID is correctly imported/defined
+        seeds::program = wormhole_program.key()
     )]
-    /// CHECK: Wormhole bridge state account. Address is checked against wormhole::program::ID if needed. Data is owned by Wormhole program.
-    pub wormhole_bridge: AccountInfo<'info>, // Changed from Account<'info, wormhole::BridgeData>
+    pub posted_vaa: Account<'info, wormhole::PostedVaa<RawPayload>>,
 
-    /// CHECK: Posted VAA account. Data is manually deserialized and verified.
-    /// Client is responsible for passing the correct account address.
-    #[account()]
-    pub posted_vaa: AccountInfo<'info>, // Load as AccountInfo
+    // Registered emitter for the VAA's source chain; a missing PDA means the chain isn't trusted
+    #[account(
+        seeds = [ForeignEmitter::SEED, &posted_vaa.emitter_chain().to_le_bytes()[..]],
+        bump = foreign_emitter.bump,
+        constraint = foreign_emitter.chain == posted_vaa.emitter_chain() @ ErrorCode::InvalidEmitterChain,
+        constraint = foreign_emitter.verify(posted_vaa.emitter_address()) @ ErrorCode::InvalidEmitterAddress
+    )]
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+
+    // Replay protection: one record per (emitter chain, emitter address, sequence)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BridgeRequest::size(posted_vaa.payload_size() as usize),
+        seeds = [
+            BridgeRequest::SEED,
+            &posted_vaa.emitter_chain().to_le_bytes()[..],
+            posted_vaa.emitter_address().as_ref(),
+            &posted_vaa.sequence().to_le_bytes()[..]
+        ],
+        bump
+    )]
+    pub bridge_request: Account<'info, BridgeRequest>,
 
     #[account(
         mut,
-        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref()],
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
         bump = pool.bump
     )]
     pub pool: Account<'info, Pool>,
 
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     /// CHECK: Authority PDA, seeds checked. Used as signer if needed.
     #[account(
         seeds = [b"authority".as_ref(), pool.key().as_ref()],
@@ -46,10 +77,10 @@ pub struct ProcessVAA<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint)]
-    pub token_a_mint: Account<'info, Mint>,
-    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint)]
-    pub token_b_mint: Account<'info, Mint>,
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -57,7 +88,7 @@ pub struct ProcessVAA<'info> {
         bump = pool.token_a_bump,
         constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
     )]
-    pub token_a_account: Account<'info, TokenAccount>,
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -65,7 +96,7 @@ pub struct ProcessVAA<'info> {
         bump = pool.token_b_bump,
         constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
     )]
-    pub token_b_account: Account<'info, TokenAccount>,
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -73,7 +104,49 @@ pub struct ProcessVAA<'info> {
         bump = pool.lp_mint_bump,
         constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    // Pool-owned LP account receiving the liquidity locked by the first deposit
+    #[account(
+        mut,
+        seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+        bump = pool.locked_lp_bump
+    )]
+    pub locked_lp_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Outflow caps for the pool and for the source chain, one per token (see RateLimit).
+    // Seeds pin the addresses; an account that was never configured is empty and means no cap.
+    /// CHECK: RateLimit PDA or empty, deserialized in the handler
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, pool.key().as_ref(), pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub pool_rate_limit_a: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized in the handler
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, pool.key().as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub pool_rate_limit_b: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized in the handler
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, &posted_vaa.emitter_chain().to_le_bytes()[..], pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub chain_rate_limit_a: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized in the handler
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, &posted_vaa.emitter_chain().to_le_bytes()[..], pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub chain_rate_limit_b: UncheckedAccount<'info>,
 
     /// CHECK: Recipient address derived from VAA payload. Account type checked in handler.
     #[account(mut)]
@@ -85,26 +158,30 @@ pub struct ProcessVAA<'info> {
         associated_token::mint = lp_mint,
         associated_token::authority = recipient
     )]
-    pub recipient_lp_token_account: Account<'info, TokenAccount>,
+    pub recipient_lp_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = token_a_mint,
-        associated_token::authority = recipient
+        associated_token::authority = recipient,
+        associated_token::token_program = token_a_program
     )]
-    pub recipient_token_a_account: Account<'info, TokenAccount>,
+    pub recipient_token_a_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = token_b_mint,
-        associated_token::authority = recipient
+        associated_token::authority = recipient,
+        associated_token::token_program = token_b_program
     )]
-    pub recipient_token_b_account: Account<'info, TokenAccount>,
+    pub recipient_token_b_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -117,119 +194,445 @@ pub fn handler(
     msg!("Processing VAA...");
 
     // --- VAA Verification ---
-    // Manually deserialize and verify the VAA data from the account info
-    let posted_vaa_account_info = &ctx.accounts.posted_vaa;
-    let posted_vaa_data = posted_vaa_account_info.try_borrow_data()?;
-    // Use Vaa::read with Cursor
-    let mut cursor = Cursor::new(&posted_vaa_data[..]);
-    let vaa: Vaa = Vaa::read(&mut cursor)?;
-
-    // TODO: Add proper VAA verification logic here using wormhole_bridge data
-    // This typically involves checking the guardian signatures against the current guardian set
-    // stored in the wormhole_bridge account. This is crucial for security.
-    // Example placeholder:
-    // verify_vaa(&ctx.accounts.wormhole_bridge, &vaa)?;
-
-    // Optional: Check against replay using BridgeRequest account
-    /*
-    let bridge_request = &mut ctx.accounts.bridge_request;
-    require!(bridge_request.status == BridgeStatus::Pending, ErrorCode::VaaAlreadyProcessed);
-    */
+    // Guardian signatures, ownership, the hash binding and the emitter are enforced by the account constraints
+    let vaa = &ctx.accounts.posted_vaa;
+
+    // --- Replay Protection ---
+    // A freshly created record is Pending, anything else means this VAA was already redeemed
+    require!(
+        ctx.accounts.bridge_request.status == BridgeStatus::Pending,
+        ErrorCode::VaaAlreadyProcessed
+    );
 
     // --- Payload Processing ---
-    // Extract the raw payload bytes by matching the PayloadKind enum
-    let payload: &[u8] = match &vaa.body.payload {
-        PayloadKind::Binary(bytes) => bytes.as_slice(), // Use Binary variant
-        _ => return err!(ErrorCode::UnsupportedPayloadKind), // Error for other variants
-    };
-    require!(!payload.is_empty(), ErrorCode::InvalidVaaPayload);
-
-    let operation_code = payload[0];
-    let specific_payload_data = &payload[1..];
+    let envelope = decode_vaa_payload(vaa)?;
+    let bridge_payload = envelope.payload;
+
+    // --- Pool Link ---
+    // The message must come from the chain the pool is linked to, and enveloped payloads
+    // must also name the linked pool as their sender. Bare version 1 payloads carry no sender;
+    // what binds them to this pool is the body: the pool_id of liquidity messages and the
+    // address of LINK_POOL are checked in `execute`.
+    let pool = &ctx.accounts.pool;
+    pool.require_linked()?;
+    require!(vaa.emitter_chain() == pool.linked_chain_id, ErrorCode::PoolLinkMismatch);
+    if envelope.version > PROTOCOL_VERSION_1 {
+        require!(envelope.source_address == pool.linked_address, ErrorCode::PoolLinkMismatch);
+    }
 
     msg!("VAA Details: Chain={}, Addr={}, Seq={}",
-        vaa.body.emitter_chain, // Access via vaa.body
-        hex::encode(vaa.body.emitter_address), // Access via vaa.body
-        vaa.body.sequence // Access via vaa.body
+        vaa.emitter_chain(),
+        hex::encode(vaa.emitter_address()),
+        vaa.sequence()
     );
-    msg!("Processing Operation Code: {}", operation_code);
-
-    // TODO: Add checks for emitter_chain and emitter_address if needed
-    // require!(vaa.body.emitter_chain == SUI_CHAIN_ID, ErrorCode::InvalidEmitterChain);
-    // require!(vaa.body.emitter_address == SUI_BRIDGE_ADDRESS, ErrorCode::InvalidEmitterAddress);
-
-    match operation_code {
-        0 => { // AddLiquidityCompletion
-            msg!("Processing Add Liquidity Completion...");
-            let completion_payload = AddLiquidityCompletionPayload::try_from_slice(specific_payload_data)
-                .map_err(|_| error!(ErrorCode::InvalidVaaPayload))?;
-            msg!("Payload: {:?}", completion_payload);
-
-            require!(
-                ctx.accounts.recipient.key().to_bytes() == completion_payload.recipient_address,
-                ErrorCode::RecipientMismatch
-            );
-            require!(
-                ctx.accounts.pool.pool_id == completion_payload.original_pool_id,
-                ErrorCode::PoolIdMismatch
-            );
-
-            mint_lp_tokens(
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.lp_mint.to_account_info(),
-                ctx.accounts.recipient_lp_token_account.to_account_info(),
-                ctx.accounts.pool_authority.to_account_info(),
-                ctx.accounts.pool.key(),
-                completion_payload.lp_amount_to_mint,
-                ctx.bumps.pool_authority,
-            )?;
-            msg!("Minted {} LP tokens to {}", completion_payload.lp_amount_to_mint, ctx.accounts.recipient.key());
+    msg!("Processing Operation: {:?} (version {}, nonce {})", bridge_payload.op_code(), envelope.version, envelope.nonce);
+
+    let pool_key = ctx.accounts.pool.key();
+    let linked_address = ctx.accounts.pool.linked_address;
+    let op = bridge_payload.op_code() as u8;
+    let outcome = PoolOperation {
+        pool: &mut ctx.accounts.pool,
+        pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        authority_bump: ctx.bumps.pool_authority,
+        token_a_mint: &ctx.accounts.token_a_mint,
+        token_b_mint: &ctx.accounts.token_b_mint,
+        token_a_account: &ctx.accounts.token_a_account,
+        token_b_account: &ctx.accounts.token_b_account,
+        lp_mint: &ctx.accounts.lp_mint,
+        recipient: ctx.accounts.recipient.key(),
+        recipient_lp_token_account: ctx.accounts.recipient_lp_token_account.to_account_info(),
+        locked_lp_account: ctx.accounts.locked_lp_account.to_account_info(),
+        recipient_token_a_account: ctx.accounts.recipient_token_a_account.to_account_info(),
+        recipient_token_b_account: ctx.accounts.recipient_token_b_account.to_account_info(),
+        rate_limits: [
+            ctx.accounts.pool_rate_limit_a.to_account_info(),
+            ctx.accounts.pool_rate_limit_b.to_account_info(),
+            ctx.accounts.chain_rate_limit_a.to_account_info(),
+            ctx.accounts.chain_rate_limit_b.to_account_info(),
+        ],
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_a_program: ctx.accounts.token_a_program.to_account_info(),
+        token_b_program: ctx.accounts.token_b_program.to_account_info(),
+        source_chain: vaa.emitter_chain(),
+    }
+    .execute(bridge_payload)?;
 
+    // Record the redeemed VAA. Failed and delayed operations are recorded too, so the VAA
+    // can't be redeemed again; they are finished by the retry, refund or release instructions.
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    record(bridge_request, vaa, ctx.bumps.bridge_request, outcome)?;
+    bridge_request.pool = pool_key;
+    bridge_request.linked_address = linked_address;
+    emit!(VaaProcessed::new(bridge_request, op));
+
+    msg!("VAA processed successfully.");
+    Ok(())
+}
+
+// Validate the payload of a verified VAA and decode it. Enveloped payloads name both chains;
+// they must agree with the VAA and with us. Version 1 payloads carry neither, the registered
+// emitter is all we have.
+pub fn decode_vaa_payload(vaa: &wormhole::PostedVaa<RawPayload>) -> Result<PayloadEnvelope> {
+    let payload: &[u8] = &vaa.data().0;
+    require!(
+        !payload.is_empty()
+            && payload.len() == vaa.payload_size() as usize
+            && payload.len() <= BridgeRequest::MAX_PAYLOAD_SIZE,
+        ErrorCode::InvalidVaaPayload
+    );
+
+    let envelope = PayloadEnvelope::decode(payload)?;
+    if envelope.version > PROTOCOL_VERSION_1 {
+        require!(envelope.source_chain == vaa.emitter_chain(), ErrorCode::InvalidEmitterChain);
+        require!(envelope.target_chain == wormhole::CHAIN_ID_SOLANA, ErrorCode::InvalidTargetChain);
+    }
+    Ok(envelope)
+}
+
+// Mark a VAA as redeemed in its replay-protection record
+pub fn record_completed(
+    bridge_request: &mut BridgeRequest,
+    vaa: &wormhole::PostedVaa<RawPayload>,
+    bump: u8,
+) -> Result<()> {
+    record(bridge_request, vaa, bump, Outcome::Completed)
+}
+
+// Fill the replay-protection record of a VAA; any status but Pending blocks a second redemption
+pub fn record(
+    bridge_request: &mut BridgeRequest,
+    vaa: &wormhole::PostedVaa<RawPayload>,
+    bump: u8,
+    outcome: Outcome,
+) -> Result<()> {
+    bridge_request.wormhole_sequence = vaa.sequence();
+    bridge_request.emitter_chain = vaa.emitter_chain();
+    bridge_request.emitter_address = *vaa.emitter_address();
+    apply_outcome(bridge_request, outcome);
+    bridge_request.payload = vaa.data().0.clone();
+    bridge_request.created_at = Clock::get()?.unix_timestamp;
+    bridge_request.bump = bump;
+    Ok(())
+}
+
+// How a bridge operation ended, see `PoolOperation::execute`
+pub enum Outcome {
+    Completed,
+    CompletedWithSurplus { surplus_a: u64, surplus_b: u64 }, // Deposit taken in part, see `BridgeRequest`
+    Delayed { release_at: i64 },
+    Failed(BridgeFailure),
+}
+
+// Store an operation's outcome in its bridge request
+pub fn apply_outcome(bridge_request: &mut BridgeRequest, outcome: Outcome) {
+    match outcome {
+        Outcome::Completed => {
+            bridge_request.status = BridgeStatus::Completed;
+            bridge_request.failure_reason = BridgeFailure::None;
+        }
+        Outcome::CompletedWithSurplus { surplus_a, surplus_b } => {
+            bridge_request.status = BridgeStatus::Completed;
+            bridge_request.failure_reason = BridgeFailure::None;
+            bridge_request.surplus_a = surplus_a;
+            bridge_request.surplus_b = surplus_b;
+            msg!("Deposit off the pool ratio, surplus A={}, B={} can be refunded", surplus_a, surplus_b);
         }
-        1 => { // RemoveLiquidityCompletion
-            msg!("Processing Remove Liquidity Completion...");
-            let completion_payload = RemoveLiquidityCompletionPayload::try_from_slice(specific_payload_data)
-                 .map_err(|_| error!(ErrorCode::InvalidVaaPayload))?;
-            msg!("Payload: {:?}", completion_payload);
-
-            require!(
-                ctx.accounts.recipient.key().to_bytes() == completion_payload.recipient_address,
-                ErrorCode::RecipientMismatch
-            );
-            require!(
-                ctx.accounts.pool.pool_id == completion_payload.original_pool_id,
-                ErrorCode::PoolIdMismatch
-            );
-
-            transfer_pool_tokens(
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.token_a_account.to_account_info(),
-                ctx.accounts.recipient_token_a_account.to_account_info(),
-                ctx.accounts.pool_authority.to_account_info(),
-                ctx.accounts.pool.key(),
-                completion_payload.amount_a_to_transfer,
-                ctx.bumps.pool_authority,
-            )?;
-             msg!("Transferred {} Token A to {}", completion_payload.amount_a_to_transfer, ctx.accounts.recipient.key());
-
-             transfer_pool_tokens(
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.token_b_account.to_account_info(),
-                ctx.accounts.recipient_token_b_account.to_account_info(),
-                ctx.accounts.pool_authority.to_account_info(),
-                ctx.accounts.pool.key(),
-                completion_payload.amount_b_to_transfer,
-                ctx.bumps.pool_authority,
-            )?;
-            msg!("Transferred {} Token B to {}", completion_payload.amount_b_to_transfer, ctx.accounts.recipient.key());
+        Outcome::Delayed { release_at } => {
+            bridge_request.status = BridgeStatus::Delayed;
+            bridge_request.failure_reason = BridgeFailure::None;
+            bridge_request.release_at = release_at;
+            msg!("Withdrawal over the rate limit, delayed until {}", release_at);
+        }
+        Outcome::Failed(reason) => {
+            bridge_request.status = BridgeStatus::Failed;
+            bridge_request.failure_reason = reason;
+            msg!("Bridge operation failed: {:?}", reason);
+        }
+    }
+}
+
+// Pool-level accounts a bridge operation executes against, shared by `process_vaa` and
+// `retry_bridge_request`
+pub struct PoolOperation<'a, 'info> {
+    pub pool: &'a mut Account<'info, Pool>,
+    pub pool_authority: AccountInfo<'info>,
+    pub authority_bump: u8,
+    pub token_a_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_b_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_a_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_b_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub lp_mint: &'a InterfaceAccount<'info, Mint>,
+    pub recipient: Pubkey,
+    pub recipient_lp_token_account: AccountInfo<'info>,
+    pub locked_lp_account: AccountInfo<'info>,
+    pub recipient_token_a_account: AccountInfo<'info>,
+    pub recipient_token_b_account: AccountInfo<'info>,
+    pub rate_limits: [AccountInfo<'info>; 4], // Pool A, pool B, chain A, chain B
+    pub token_program: AccountInfo<'info>,     // LP mint's program
+    pub token_a_program: AccountInfo<'info>,
+    pub token_b_program: AccountInfo<'info>,
+    pub source_chain: u16,                     // Chain the message came from
+}
+
+impl<'a, 'info> PoolOperation<'a, 'info> {
+    // Execute `payload` against the pool. Conditions that can clear up later end in
+    // `Outcome::Failed` instead of an error; invalid messages and accounts still revert.
+    pub fn execute(self, payload: BridgePayload) -> Result<Outcome> {
+        match payload {
+            BridgePayload::AddLiquidity(deposit) => {
+                msg!("Processing remote Add Liquidity...");
+                msg!("Payload: {:?}", deposit);
+
+                require!(deposit.pool_id == self.pool.pool_id, ErrorCode::PoolIdMismatch);
+
+                // LP tokens for a remote deposit go to the Solana account named in the message
+                let recipient_address = deposit.recipient_address.ok_or(ErrorCode::InvalidVaaPayload)?;
+                require!(self.recipient.to_bytes() == recipient_address, ErrorCode::RecipientMismatch);
+
+                // Deposits are only accepted into active pools
+                if self.pool.require_active().is_err() {
+                    return Ok(Outcome::Failed(BridgeFailure::PoolNotActive));
+                }
+
+                // Price the deposit like `add_liquidity` instead of trusting an LP amount from the message.
+                // The tokens stay on the linked pool, so they are booked as remote reserves.
+                let before = self.pool.snapshot();
+                let (reserve_a, reserve_b) = self.pool.combined_reserves()?;
+                let lp_supply = self.lp_mint.supply;
+                let curve = self.pool.curve(Clock::get()?.unix_timestamp)?;
+                let (amount_a, amount_b, liquidity_minted) = match quote_deposit(
+                    &curve,
+                    deposit.amount_a,
+                    deposit.amount_b,
+                    0,
+                    0,
+                    reserve_a,
+                    reserve_b,
+                    lp_supply,
+                ) {
+                    Ok(quote) => quote,
+                    Err(err)
+                        if err == ErrorCode::ZeroLiquidityMinted.into()
+                            || err == ErrorCode::InsufficientInitialLiquidity.into() =>
+                    {
+                        return Ok(Outcome::Failed(BridgeFailure::ZeroLiquidity));
+                    }
+                    Err(err) => return Err(err),
+                };
+
+                mint_lp_tokens(
+                    self.token_program.clone(),
+                    self.lp_mint.to_account_info(),
+                    self.recipient_lp_token_account,
+                    self.pool_authority.clone(),
+                    self.pool.key(),
+                    liquidity_minted,
+                    self.authority_bump,
+                )?;
+                msg!("Minted {} LP tokens to {}", liquidity_minted, self.recipient);
+
+                // The first deposit can come from the linked pool, it locks the minimum liquidity too
+                let locked = locked_liquidity(lp_supply);
+                if locked > 0 {
+                    mint_lp_tokens(
+                        self.token_program,
+                        self.lp_mint.to_account_info(),
+                        self.locked_lp_account,
+                        self.pool_authority,
+                        self.pool.key(),
+                        locked,
+                        self.authority_bump,
+                    )?;
+                }
+
+                let pool = self.pool;
+                let clock = Clock::get()?;
+                // Only what the deposit is priced on is booked, like `add_liquidity`
+                pool.credit_remote_reserves(amount_a, amount_b)?;
+                pool.total_liquidity = pool
+                    .total_liquidity
+                    .checked_add(liquidity_minted)
+                    .and_then(|total| total.checked_add(locked))
+                    .ok_or(ErrorCode::Overflow)?;
+                pool.last_updated_at = clock.unix_timestamp;
+
+                // Nothing reached the vaults, the deposit is in the remote reserves
+                emit!(LiquidityAdded {
+                    pool: pool.key(),
+                    user: self.recipient,
+                    source_chain: self.source_chain,
+                    amount_a,
+                    amount_b,
+                    lp_minted: liquidity_minted,
+                    before,
+                    after: pool.snapshot(),
+                    timestamp: clock.unix_timestamp,
+                });
+
+                // An off-ratio deposit leaves part of it on the linked pool, owed back to the depositor
+                let surplus_a = deposit.amount_a.checked_sub(amount_a).ok_or(ErrorCode::Underflow)?;
+                let surplus_b = deposit.amount_b.checked_sub(amount_b).ok_or(ErrorCode::Underflow)?;
+                if surplus_a > 0 || surplus_b > 0 {
+                    return Ok(Outcome::CompletedWithSurplus { surplus_a, surplus_b });
+                }
+            }
+            BridgePayload::RemoveLiquidity(withdrawal) => {
+                msg!("Processing remote Remove Liquidity...");
+                msg!("Payload: {:?}", withdrawal);
+
+                require!(withdrawal.pool_id == self.pool.pool_id, ErrorCode::PoolIdMismatch);
+                require!(
+                    self.recipient.to_bytes() == withdrawal.recipient_address,
+                    ErrorCode::RecipientMismatch
+                );
+
+                // Checked before the rate limits so a failed withdrawal isn't charged
+                let (local_a, local_b) = self.pool.lp_reserves();
+                if withdrawal.amount_a > local_a || withdrawal.amount_b > local_b {
+                    return Ok(Outcome::Failed(BridgeFailure::InsufficientReserves));
+                }
+
+                // Over-limit withdrawals are parked instead of paid; anyone can release them
+                // once the cooldown has passed (`release_bridge_request`)
+                let now = Clock::get()?.unix_timestamp;
+                let [pool_a, pool_b, chain_a, chain_b] = self.rate_limits;
+                let limits = [
+                    (pool_a, withdrawal.amount_a),
+                    (pool_b, withdrawal.amount_b),
+                    (chain_a, withdrawal.amount_a),
+                    (chain_b, withdrawal.amount_b),
+                ];
+                if let Some(release_delay) = consume_outflow(&limits, now)? {
+                    let release_at = now.checked_add(release_delay).ok_or(ErrorCode::Overflow)?;
+                    return Ok(Outcome::Delayed { release_at });
+                }
+
+                RemoteWithdrawalPayout {
+                    pool: self.pool,
+                    recipient: self.recipient,
+                    pool_authority: self.pool_authority,
+                    authority_bump: self.authority_bump,
+                    token_a_mint: self.token_a_mint,
+                    token_b_mint: self.token_b_mint,
+                    token_a_account: self.token_a_account,
+                    token_b_account: self.token_b_account,
+                    recipient_token_a_account: self.recipient_token_a_account,
+                    recipient_token_b_account: self.recipient_token_b_account,
+                    token_a_program: self.token_a_program,
+                    token_b_program: self.token_b_program,
+                }
+                .pay(&withdrawal)?;
+                msg!("Paid A={}, B={} to {}", withdrawal.amount_a, withdrawal.amount_b, self.recipient);
+            }
+            BridgePayload::LinkPool(link) => {
+                // The remote pool linked back to us; it must be the pool we are linked to
+                require!(link.pool_address == self.pool.linked_address, ErrorCode::PoolLinkMismatch);
+                msg!("Link confirmed by pool {}", hex::encode(link.pool_address));
+            }
+            BridgePayload::CreatePool(_) => {
+                // Valid messages, but not handled by a pool-level VAA
+                msg!("Operation not supported by process_vaa: {:?}", payload.op_code());
+                return err!(ErrorCode::UnsupportedPayloadKind);
+            }
+        }
+        Ok(Outcome::Completed)
+    }
+}
 
+// Draw `amount` from each configured rate limit. Either every limit has room and all of them
+// are consumed (returns None), or nothing is consumed and the longest release delay among
+// the exceeded limits is returned.
+pub fn consume_outflow(limits: &[(AccountInfo, u64)], now: i64) -> Result<Option<i64>> {
+    let mut loaded = Vec::with_capacity(limits.len());
+    let mut release_delay = None;
+    for (info, amount) in limits {
+        // Never configured: no cap for this scope
+        if info.data_is_empty() {
+            continue;
         }
-        _ => {
-            msg!("Unknown operation code in payload: {}", operation_code);
-            return err!(ErrorCode::InvalidBridgeOperation);
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidAuthority);
+        let mut limit = RateLimit::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        limit.refill(now);
+        if !limit.allows(*amount) {
+            release_delay = release_delay.max(Some(limit.release_delay));
         }
+        loaded.push((info, limit, *amount));
     }
 
-    msg!("VAA processed successfully.");
-    Ok(())
+    if release_delay.is_some() {
+        return Ok(release_delay);
+    }
+    for (info, mut limit, amount) in loaded {
+        limit.consume(amount)?;
+        limit.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    Ok(None)
+}
+
+// Pays a withdrawal requested by the linked pool out of the vaults. The remote LP was burned
+// there, so the tokens the linked pool keeps are booked as remote reserves.
+pub struct RemoteWithdrawalPayout<'a, 'info> {
+    pub pool: &'a mut Account<'info, Pool>,
+    pub recipient: Pubkey,
+    pub pool_authority: AccountInfo<'info>,
+    pub authority_bump: u8,
+    pub token_a_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_b_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_a_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_b_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub recipient_token_a_account: AccountInfo<'info>,
+    pub recipient_token_b_account: AccountInfo<'info>,
+    pub token_a_program: AccountInfo<'info>,
+    pub token_b_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> RemoteWithdrawalPayout<'a, 'info> {
+    pub fn pay(self, withdrawal: &RemoveLiquidityPayload) -> Result<()> {
+        let clock = Clock::get()?;
+        // Price accumulators are weighted with the reserves held up to now
+        self.pool.accrue_prices(clock.unix_timestamp)?;
+        let before = self.pool.snapshot();
+
+        // Paid out of the local LP reserves, never out of accrued protocol fees.
+        // The amounts are what leaves the vaults; a transfer fee is borne by the recipient.
+        self.pool.withdraw_reserves(withdrawal.amount_a, withdrawal.amount_b)?;
+
+        transfer_pool_tokens(
+            self.token_a_program,
+            self.token_a_account.to_account_info(),
+            self.token_a_mint,
+            self.recipient_token_a_account,
+            self.pool_authority.clone(),
+            self.pool.key(),
+            withdrawal.amount_a,
+            self.authority_bump,
+        )?;
+        transfer_pool_tokens(
+            self.token_b_program,
+            self.token_b_account.to_account_info(),
+            self.token_b_mint,
+            self.recipient_token_b_account,
+            self.pool_authority,
+            self.pool.key(),
+            withdrawal.amount_b,
+            self.authority_bump,
+        )?;
+
+        self.pool.credit_remote_reserves(withdrawal.amount_a, withdrawal.amount_b)?;
+        self.pool.last_updated_at = clock.unix_timestamp;
+
+        // The LP tokens were burned on the remote chain; here only the reserves move
+        emit!(LiquidityRemoved {
+            pool: self.pool.key(),
+            user: self.recipient,
+            target_chain: wormhole::CHAIN_ID_SOLANA,
+            amount_a: withdrawal.amount_a,
+            amount_b: withdrawal.amount_b,
+            lp_burned: 0,
+            before,
+            after: self.pool.snapshot(),
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::curve::validate_ramp;
+use crate::state::{Config, CurveType, Pool};
+use crate::errors::ErrorCode;
+use crate::events::AmplificationRamped;
+
+#[derive(Accounts)]
+pub struct RampAmplification<'info> {
+    // Program admin, the only account allowed to change a pool's amplification
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // StableSwap pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump,
+        constraint = pool.curve_type == CurveType::StableSwap @ ErrorCode::NotStableSwapPool
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+// Handler function for moving the amplification linearly from its current value to
+// `target_amplification`, reached at `ramp_end`. Changing A in one step would move every LP's
+// position along the curve at once, so the change is spread out and bounded (see `validate_ramp`).
+pub fn handler(ctx: Context<RampAmplification>, target_amplification: u64, ramp_end: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let current = pool.amplification(now);
+    validate_ramp(current, target_amplification, pool.amp_ramp_start, now, ramp_end)?;
+
+    pool.amp_initial = current;
+    pool.amp_target = target_amplification;
+    pool.amp_ramp_start = now;
+    pool.amp_ramp_end = ramp_end;
+    pool.last_updated_at = now;
+
+    emit!(AmplificationRamped {
+        pool: pool.key(),
+        initial_amplification: current,
+        target_amplification,
+        start: now,
+        end: ramp_end,
+    });
+
+    msg!("Amplification ramping from {} to {} until {}", current, target_amplification, ramp_end);
+
+    Ok(())
+}
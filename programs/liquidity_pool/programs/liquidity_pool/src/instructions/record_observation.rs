@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::{Oracle, Pool};
+
+#[derive(Accounts)]
+pub struct RecordObservation<'info> {
+    #[account(
+        mut, // Price accumulators are brought up to date
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [Oracle::SEED, pool.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, Oracle>,
+}
+
+// Handler function for appending an observation outside of swaps.
+// Lets keepers extend the history of quiet pools; anyone can call it.
+pub fn handler(ctx: Context<RecordObservation>) -> Result<()> {
+    let observation = ctx.accounts.pool.accrue_prices(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.oracle.write(observation);
+    Ok(())
+}
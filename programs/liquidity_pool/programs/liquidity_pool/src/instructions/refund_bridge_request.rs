@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{BridgeRequest, BridgeStatus, Config, Pool};
+use crate::errors::ErrorCode;
+use crate::events::{BridgeRequestStatusChanged, DepositRefunded};
+use crate::messaging::OutboundMessage;
+use crate::payloads::{BridgePayload, PayloadEnvelope, RemoveLiquidityPayload};
+
+#[derive(Accounts)]
+pub struct RefundBridgeRequest<'info> {
+    // Recipient of the failed deposit, the only one who can redirect it. Pays the Wormhole fee.
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Deposit recorded as Failed, or Completed with a surplus, by `process_vaa`
+    #[account(
+        mut,
+        seeds = [
+            BridgeRequest::SEED,
+            &bridge_request.emitter_chain.to_le_bytes()[..],
+            bridge_request.emitter_address.as_ref(),
+            &bridge_request.wormhole_sequence.to_le_bytes()[..]
+        ],
+        bump = bridge_request.bump,
+        constraint = matches!(bridge_request.status, BridgeStatus::Failed | BridgeStatus::Completed)
+            @ ErrorCode::BridgeRequestNotRefundable,
+        constraint = bridge_request.pool == pool.key() @ ErrorCode::PoolIdMismatch
+    )]
+    pub bridge_request: Account<'info, BridgeRequest>,
+
+    // The refund is sent from this pool to the pool that sent the deposit, so that must still be
+    // the linked one
+    #[account(
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump,
+        constraint = pool.linked_chain_id == bridge_request.emitter_chain @ ErrorCode::PoolLinkMismatch,
+        constraint = pool.linked_address == bridge_request.linked_address @ ErrorCode::PoolLinkMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Wormhole core bridge accounts used to publish the refund
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::BridgeData::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_bridge: Account<'info, wormhole::BridgeData>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::FeeCollector::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
+
+    /// CHECK: Emitter PDA of the pool, only used to sign the message.
+    #[account(
+        seeds = [wormhole::SEED_PREFIX_EMITTER, pool.key().as_ref()],
+        bump
+    )]
+    pub wormhole_emitter: AccountInfo<'info>,
+
+    /// CHECK: Sequence tracker of our emitter, created and incremented by the core bridge.
+    #[account(
+        mut,
+        seeds = [wormhole::SequenceTracker::SEED_PREFIX, wormhole_emitter.key().as_ref()],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_sequence: AccountInfo<'info>,
+
+    // Fresh keypair the core bridge writes the message into
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Handler function for giving back a remote deposit that couldn't be executed, or the part of
+// one that was off the pool ratio. The deposited tokens never left the source pool, so the
+// compensating message is a REMOVE_LIQUIDITY for those amounts to `refund_address` on the source
+// chain. Remote reserves are untouched, none of it was booked. Failed withdrawals can only be retried.
+pub fn handler(ctx: Context<RefundBridgeRequest>, refund_address: [u8; 32]) -> Result<()> {
+    msg!("Refunding failed bridge request...");
+    require!(refund_address != [0u8; 32], ErrorCode::InvalidRemoteRecipient);
+
+    let deposit = match PayloadEnvelope::decode(&ctx.accounts.bridge_request.payload)?.payload {
+        BridgePayload::AddLiquidity(deposit) => deposit,
+        _ => return err!(ErrorCode::BridgeRequestNotRefundable),
+    };
+    require!(deposit.pool_id == ctx.accounts.pool.pool_id, ErrorCode::PoolIdMismatch);
+    require!(
+        deposit.recipient_address == Some(ctx.accounts.recipient.key().to_bytes()),
+        ErrorCode::RecipientMismatch
+    );
+
+    // A failed deposit is given back whole, a completed one only its surplus
+    let bridge_request = &ctx.accounts.bridge_request;
+    let (amount_a, amount_b) = if bridge_request.status == BridgeStatus::Failed {
+        (deposit.amount_a, deposit.amount_b)
+    } else {
+        (bridge_request.surplus_a, bridge_request.surplus_b)
+    };
+    require!(amount_a > 0 || amount_b > 0, ErrorCode::BridgeRequestNotRefundable);
+
+    let payload = BridgePayload::RemoveLiquidity(RemoveLiquidityPayload {
+        pool_id: deposit.pool_id,
+        amount_a,
+        amount_b,
+        recipient_address: refund_address,
+    });
+
+    let pool_address = ctx.accounts.pool.key().to_bytes();
+    let source_chain = ctx.accounts.bridge_request.emitter_chain;
+    let bridge_fee = ctx.accounts.wormhole_bridge.fee();
+    OutboundMessage {
+        wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
+        wormhole_bridge: ctx.accounts.wormhole_bridge.to_account_info(),
+        wormhole_fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
+        wormhole_emitter: ctx.accounts.wormhole_emitter.to_account_info(),
+        wormhole_sequence: ctx.accounts.wormhole_sequence.to_account_info(),
+        wormhole_message: ctx.accounts.wormhole_message.to_account_info(),
+        payer: ctx.accounts.recipient.to_account_info(),
+        clock: ctx.accounts.clock.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .post(bridge_fee, ctx.bumps.wormhole_emitter, pool_address, source_chain, payload)?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    if bridge_request.status == BridgeStatus::Failed {
+        bridge_request.status = BridgeStatus::Refunded;
+        emit!(BridgeRequestStatusChanged::new(
+            bridge_request.key(),
+            bridge_request,
+            BridgeStatus::Failed,
+            timestamp,
+        ));
+    } else {
+        // The deposit itself stays completed, only its surplus is settled
+        bridge_request.surplus_a = 0;
+        bridge_request.surplus_b = 0;
+    }
+    emit!(DepositRefunded {
+        bridge_request: bridge_request.key(),
+        pool: ctx.accounts.pool.key(),
+        target_chain: source_chain,
+        refund_address,
+        amount_a,
+        amount_b,
+        timestamp,
+    });
+
+    msg!(
+        "Refunded A={}, B={} to {} on chain {}",
+        amount_a,
+        amount_b,
+        hex::encode(refund_address),
+        source_chain
+    );
+
+    Ok(())
+}
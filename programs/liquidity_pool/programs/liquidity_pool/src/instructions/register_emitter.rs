@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{Config, ForeignEmitter};
+use crate::errors::ErrorCode;
+use crate::events::ForeignEmitterChanged;
+
+#[derive(Accounts)]
+#[instruction(chain: u16)]
+pub struct RegisterEmitter<'info> {
+    // Program admin, pays for the emitter account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // Emitter account (PDA per chain ID) - needs to be initialized
+    #[account(
+        init,
+        payer = admin,
+        space = ForeignEmitter::SIZE,
+        seeds = [ForeignEmitter::SEED, &chain.to_le_bytes()[..]],
+        bump
+    )]
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Handler function for trusting an emitter on a foreign chain
+pub fn handler(ctx: Context<RegisterEmitter>, chain: u16, address: [u8; 32]) -> Result<()> {
+    validate_emitter(chain, &address)?;
+
+    let foreign_emitter = &mut ctx.accounts.foreign_emitter;
+    foreign_emitter.chain = chain;
+    foreign_emitter.address = address;
+    foreign_emitter.bump = ctx.bumps.foreign_emitter;
+
+    emit!(ForeignEmitterChanged {
+        chain,
+        old_address: [0u8; 32],
+        new_address: address,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Registered emitter: chain={}, address={}", chain, hex::encode(address));
+
+    Ok(())
+}
+
+// Emitters must live on a foreign chain and have a non-zero address
+pub fn validate_emitter(chain: u16, address: &[u8; 32]) -> Result<()> {
+    require!(
+        chain != 0 && chain != wormhole::CHAIN_ID_SOLANA,
+        ErrorCode::InvalidEmitterChain
+    );
+    require!(*address != [0u8; 32], ErrorCode::InvalidEmitterAddress);
+    Ok(())
+}
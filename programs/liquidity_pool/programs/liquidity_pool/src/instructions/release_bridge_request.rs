@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BridgeRequest, BridgeStatus, Config, Pool};
+use crate::errors::ErrorCode;
+use crate::events::BridgeRequestStatusChanged;
+use crate::instructions::process_vaa::RemoteWithdrawalPayout;
+use crate::payloads::{BridgePayload, PayloadEnvelope};
+
+#[derive(Accounts)]
+pub struct ReleaseBridgeRequest<'info> {
+    // Anyone can release a delayed request once its cooldown is over
+    pub payer: Signer<'info>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Withdrawal parked by `process_vaa` for exceeding a rate limit
+    #[account(
+        mut,
+        seeds = [
+            BridgeRequest::SEED,
+            &bridge_request.emitter_chain.to_le_bytes()[..],
+            bridge_request.emitter_address.as_ref(),
+            &bridge_request.wormhole_sequence.to_le_bytes()[..]
+        ],
+        bump = bridge_request.bump,
+        constraint = bridge_request.status == BridgeStatus::Delayed @ ErrorCode::BridgeRequestNotDelayed,
+        constraint = bridge_request.pool == pool.key() @ ErrorCode::PoolIdMismatch
+    )]
+    pub bridge_request: Account<'info, BridgeRequest>,
+
+    // The pool is still linked to the pool the withdrawal came from
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump,
+        constraint = pool.linked_chain_id == bridge_request.emitter_chain @ ErrorCode::PoolLinkMismatch,
+        constraint = pool.linked_address == bridge_request.linked_address @ ErrorCode::PoolLinkMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: Authority PDA, seeds checked. Signs the transfers out of the vaults.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump = pool.token_a_bump,
+        constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump = pool.token_b_bump,
+        constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Recipient named in the payload, checked in the handler
+    #[account(
+        mut,
+        constraint = recipient_token_a_account.mint == pool.token_a_mint @ ErrorCode::InvalidMint
+    )]
+    pub recipient_token_a_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_b_account.mint == pool.token_b_mint @ ErrorCode::InvalidMint
+    )]
+    pub recipient_token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+}
+
+// Handler function for paying out a withdrawal that was delayed by a rate limit.
+// The cooldown is the safeguard here; the rate limits are not charged again.
+pub fn handler(ctx: Context<ReleaseBridgeRequest>) -> Result<()> {
+    msg!("Releasing delayed bridge request...");
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= ctx.accounts.bridge_request.release_at, ErrorCode::BridgeRequestLocked);
+
+    // The stored payload was validated when the VAA was processed; it must still name this pool
+    let withdrawal = match PayloadEnvelope::decode(&ctx.accounts.bridge_request.payload)?.payload {
+        BridgePayload::RemoveLiquidity(withdrawal) => withdrawal,
+        _ => return err!(ErrorCode::InvalidBridgeOperation),
+    };
+    require!(withdrawal.pool_id == ctx.accounts.pool.pool_id, ErrorCode::PoolIdMismatch);
+    let recipient = Pubkey::new_from_array(withdrawal.recipient_address);
+    require!(
+        ctx.accounts.recipient_token_a_account.owner == recipient
+            && ctx.accounts.recipient_token_b_account.owner == recipient,
+        ErrorCode::RecipientMismatch
+    );
+
+    RemoteWithdrawalPayout {
+        pool: &mut ctx.accounts.pool,
+        recipient,
+        pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        authority_bump: ctx.bumps.pool_authority,
+        token_a_mint: &ctx.accounts.token_a_mint,
+        token_b_mint: &ctx.accounts.token_b_mint,
+        token_a_account: &ctx.accounts.token_a_account,
+        token_b_account: &ctx.accounts.token_b_account,
+        recipient_token_a_account: ctx.accounts.recipient_token_a_account.to_account_info(),
+        recipient_token_b_account: ctx.accounts.recipient_token_b_account.to_account_info(),
+        token_a_program: ctx.accounts.token_a_program.to_account_info(),
+        token_b_program: ctx.accounts.token_b_program.to_account_info(),
+    }
+    .pay(&withdrawal)?;
+
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    bridge_request.status = BridgeStatus::Completed;
+    emit!(BridgeRequestStatusChanged::new(bridge_request.key(), bridge_request, BridgeStatus::Delayed, now));
+
+    msg!("Released A={}, B={} to {}", withdrawal.amount_a, withdrawal.amount_b, recipient);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, ForeignEmitter};
+use crate::errors::ErrorCode;
+use crate::events::ForeignEmitterChanged;
+
+#[derive(Accounts)]
+pub struct RemoveEmitter<'info> {
+    // Program admin, receives the rent of the closed account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [ForeignEmitter::SEED, &foreign_emitter.chain.to_le_bytes()[..]],
+        bump = foreign_emitter.bump
+    )]
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+}
+
+// Handler function for untrusting a foreign chain; its VAAs are rejected afterwards
+pub fn handler(ctx: Context<RemoveEmitter>) -> Result<()> {
+    // The account itself is closed by Anchor on exit
+    emit!(ForeignEmitterChanged {
+        chain: ctx.accounts.foreign_emitter.chain,
+        old_address: ctx.accounts.foreign_emitter.address,
+        new_address: [0u8; 32],
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Removed emitter for chain {}", ctx.accounts.foreign_emitter.chain);
+    Ok(())
+}
@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, Burn};
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::state::Pool;
 use crate::errors::ErrorCode; // Assuming errors.rs exists
+use crate::events::LiquidityRemoved;
+use crate::math::mul_div_floor;
+use crate::native_sol::{UserTokenAccount, WSOL_SEED};
+use crate::token_extensions::amount_received;
+use wormhole_anchor_sdk::wormhole;
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
@@ -12,7 +17,7 @@ pub struct RemoveLiquidity<'info> {
     // Pool state account
     #[account(
         mut, // Pool state will be updated (total_liquidity, last_updated_at)
-        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref()],
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
         bump = pool.bump
     )]
     pub pool: Account<'info, Pool>,
@@ -25,6 +30,13 @@ pub struct RemoveLiquidity<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
+    // Pool token mints, owned by SPL Token or Token-2022
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
     // Pool's token accounts (funds transferred from here)
     #[account(
         mut,
@@ -32,7 +44,7 @@ pub struct RemoveLiquidity<'info> {
         bump = pool.token_a_bump,
         constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
     )]
-    pub token_a_account: Account<'info, TokenAccount>,
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -40,22 +52,33 @@ pub struct RemoveLiquidity<'info> {
         bump = pool.token_b_bump,
         constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
     )]
-    pub token_b_account: Account<'info, TokenAccount>,
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
 
-    // User's token accounts (funds transferred to here)
+    // User's token accounts (funds transferred to here).
+    // Either can be left out on a native SOL side to pay with / receive lamports instead.
     #[account(
         mut,
         constraint = user_token_a.mint == pool.token_a_mint @ ErrorCode::InvalidMint,
         constraint = user_token_a.owner == user.key() @ ErrorCode::InvalidOwner
     )]
-    pub user_token_a: Account<'info, TokenAccount>,
+    pub user_token_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         constraint = user_token_b.mint == pool.token_b_mint @ ErrorCode::InvalidMint,
         constraint = user_token_b.owner == user.key() @ ErrorCode::InvalidOwner
     )]
-    pub user_token_b: Account<'info, TokenAccount>,
+    pub user_token_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Temporary wSOL account standing in for the missing user token account, created and
+    // closed by the instruction (see `native_sol`)
+    /// CHECK: Uninitialized PDA, seeds checked below. Created as a token account in the handler.
+    #[account(
+        mut,
+        seeds = [WSOL_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_wsol_account: Option<UncheckedAccount<'info>>,
 
     // LP token mint
     #[account(
@@ -64,7 +87,7 @@ pub struct RemoveLiquidity<'info> {
         bump = pool.lp_mint_bump,
         constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
 
     // User's LP token account (LP tokens burned from here)
     #[account(
@@ -72,11 +95,13 @@ pub struct RemoveLiquidity<'info> {
         constraint = user_lp_token_account.mint == lp_mint.key() @ ErrorCode::InvalidMint,
         constraint = user_lp_token_account.owner == user.key() @ ErrorCode::InvalidOwner
     )]
-    pub user_lp_token_account: Account<'info, TokenAccount>,
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
 
     // System programs
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
     pub rent: Sysvar<'info, Rent>, // Not strictly needed here but often included
 }
 
@@ -88,49 +113,79 @@ pub fn handler(
     amount_b_min: u64,     // Minimum amount of token B user expects back
 ) -> Result<()> {
     msg!("Removing liquidity...");
+    // Native SOL is unwrapped to the user's lamports right after the transfer
+    let user_token_a = UserTokenAccount::resolve(
+        ctx.accounts.user_token_a.as_ref(),
+        ctx.accounts.user_wsol_account.as_ref(),
+        ctx.bumps.user_wsol_account,
+        &ctx.accounts.token_a_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_a_program,
+        &ctx.accounts.system_program,
+    )?;
+    let user_token_b = UserTokenAccount::resolve(
+        ctx.accounts.user_token_b.as_ref(),
+        ctx.accounts.user_wsol_account.as_ref(),
+        ctx.bumps.user_wsol_account,
+        &ctx.accounts.token_b_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_b_program,
+        &ctx.accounts.system_program,
+    )?;
     let pool = &mut ctx.accounts.pool;
     let clock = Clock::get()?;
 
-    // Check if pool is active
-    require!(pool.status == 0, ErrorCode::PoolPaused);
+    // Withdrawals are allowed in every pool status so LPs can always exit
     require!(liquidity_amount > 0, ErrorCode::ZeroLiquidityBurned);
 
-    let reserve_a = ctx.accounts.token_a_account.amount;
-    let reserve_b = ctx.accounts.token_b_account.amount;
+    // Price accumulators are weighted with the reserves held up to now
+    pool.accrue_prices(clock.unix_timestamp)?;
+
+    let before = pool.snapshot();
+
+    // LP is redeemed against the combined reserves, but paid out of the local ones only
+    let (reserve_a, reserve_b) = pool.combined_reserves()?;
     let lp_supply = ctx.accounts.lp_mint.supply;
 
     require!(lp_supply > 0, ErrorCode::PoolEmpty); // Cannot remove from empty pool
     require!(liquidity_amount <= ctx.accounts.user_lp_token_account.amount, ErrorCode::InsufficientLpTokens);
 
-    // Calculate the amount of token A and B to return
-    // amount = (liquidity_to_burn / total_lp_supply) * reserve
-    let amount_a_out = liquidity_amount.checked_mul(reserve_a).unwrap().checked_div(lp_supply).unwrap();
-    let amount_b_out = liquidity_amount.checked_mul(reserve_b).unwrap().checked_div(lp_supply).unwrap();
-
-    // Check against minimum amounts (slippage protection)
-    require!(amount_a_out >= amount_a_min, ErrorCode::SlippageExceeded);
-    require!(amount_b_out >= amount_b_min, ErrorCode::SlippageExceeded);
+    let (amount_a_out, amount_b_out) = quote_withdrawal(liquidity_amount, 0, 0, reserve_a, reserve_b, lp_supply)?;
+    // The minimums apply to what reaches the user, after any transfer fee
+    require!(
+        amount_received(&ctx.accounts.token_a_mint, amount_a_out)? >= amount_a_min
+            && amount_received(&ctx.accounts.token_b_mint, amount_b_out)? >= amount_b_min,
+        ErrorCode::SlippageExceeded
+    );
+    // Whatever sits on the linked pool has to be withdrawn there (`request_remove_liquidity_remote`)
+    pool.withdraw_reserves(amount_a_out, amount_b_out)?;
 
     // Transfer tokens from pool to user
+    user_token_a.open(0)?;
     transfer_pool_tokens(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_a_program.to_account_info(),
         ctx.accounts.token_a_account.to_account_info(),
-        ctx.accounts.user_token_a.to_account_info(),
+        &ctx.accounts.token_a_mint,
+        user_token_a.account.clone(),
         ctx.accounts.pool_authority.to_account_info(),
         pool.key(),
         amount_a_out,
         ctx.bumps.pool_authority, // Access bump directly
     )?;
+    user_token_a.close()?;
 
+    user_token_b.open(0)?;
     transfer_pool_tokens(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_b_program.to_account_info(),
         ctx.accounts.token_b_account.to_account_info(),
-        ctx.accounts.user_token_b.to_account_info(),
+        &ctx.accounts.token_b_mint,
+        user_token_b.account.clone(),
         ctx.accounts.pool_authority.to_account_info(),
         pool.key(),
         amount_b_out,
         ctx.bumps.pool_authority, // Access bump directly
     )?;
+    user_token_b.close()?;
 
     // Burn user's LP tokens
     burn_lp_tokens(
@@ -142,18 +197,54 @@ pub fn handler(
     )?;
 
     // Update pool state
-    pool.total_liquidity = pool.total_liquidity.checked_sub(liquidity_amount).unwrap();
+    pool.total_liquidity = pool.total_liquidity.checked_sub(liquidity_amount).ok_or(ErrorCode::Underflow)?;
     pool.last_updated_at = clock.unix_timestamp;
 
+    emit!(LiquidityRemoved {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        target_chain: wormhole::CHAIN_ID_SOLANA,
+        amount_a: amount_a_out,
+        amount_b: amount_b_out,
+        lp_burned: liquidity_amount,
+        before,
+        after: pool.snapshot(),
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Liquidity removed: A={}, B={}, LP={}", amount_a_out, amount_b_out, liquidity_amount);
 
     Ok(())
 }
 
+// Token amounts owed for burning `liquidity_amount` LP tokens against the given LP reserves.
+// Shared by `remove_liquidity` and `request_remove_liquidity_remote`.
+pub fn quote_withdrawal(
+    liquidity_amount: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64)> {
+    // Calculate the amount of token A and B to return
+    // amount = (liquidity_to_burn / total_lp_supply) * reserve, rounded down in the pool's favour
+    let amount_a_out = mul_div_floor(liquidity_amount, reserve_a, lp_supply)?;
+    let amount_b_out = mul_div_floor(liquidity_amount, reserve_b, lp_supply)?;
+
+    // Check against minimum amounts (slippage protection)
+    require!(amount_a_out >= amount_a_min, ErrorCode::SlippageExceeded);
+    require!(amount_b_out >= amount_b_min, ErrorCode::SlippageExceeded);
+
+    Ok((amount_a_out, amount_b_out))
+}
+
     // Helper function for transferring tokens FROM the pool PDA
+    #[allow(clippy::too_many_arguments)]
     pub fn transfer_pool_tokens<'info>( // Make helper public
         token_program: AccountInfo<'info>,
         source: AccountInfo<'info>, // Pool's token account
+        mint: &InterfaceAccount<'info, Mint>, // Mint of both accounts, for `transfer_checked`
         destination: AccountInfo<'info>, // User's token account
     authority: AccountInfo<'info>, // Pool authority PDA
     pool_key: Pubkey,
@@ -164,14 +255,15 @@ pub fn handler(
     let seeds = &[&b"authority"[..], pool_key.as_ref(), &[authority_bump]];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: source,
+        mint: mint.to_account_info(),
         to: destination,
-        authority: authority,
+        authority,
     };
     let cpi_program = token_program;
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
     Ok(())
 }
 
@@ -184,12 +276,12 @@ pub fn handler(
     amount: u64,
 ) -> Result<()> {
     let cpi_accounts = Burn {
-        mint: mint,
+        mint,
         from: account_to_burn_from,
-        authority: authority,
+        authority,
     };
     let cpi_program = token_program;
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::burn(cpi_ctx, amount)?;
+    token_interface::burn(cpi_ctx, amount)?;
     Ok(())
 }
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use wormhole_anchor_sdk::wormhole;
+use crate::state::Pool;
+use crate::errors::ErrorCode;
+use crate::events::LiquidityRemoved;
+use crate::instructions::remove_liquidity::{burn_lp_tokens, quote_withdrawal};
+use crate::messaging::OutboundMessage;
+use crate::payloads::{BridgePayload, RemoveLiquidityPayload};
+
+#[derive(Accounts)]
+pub struct RequestRemoveLiquidityRemote<'info> {
+    // User burning LP tokens
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Pool state account
+    #[account(
+        mut, // total_liquidity and last_updated_at are updated
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // LP token mint
+    #[account(
+        mut, // Supply will decrease
+        seeds = [b"lp_mint".as_ref(), pool.key().as_ref()],
+        bump = pool.lp_mint_bump,
+        constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    // User's LP token account (LP tokens burned from here)
+    #[account(
+        mut,
+        constraint = user_lp_token_account.mint == lp_mint.key() @ ErrorCode::InvalidMint,
+        constraint = user_lp_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Wormhole core bridge accounts used to ask Sui to release the funds
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::BridgeData::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_bridge: Account<'info, wormhole::BridgeData>,
+
+    #[account(
+        mut,
+        seeds = [wormhole::FeeCollector::SEED_PREFIX],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
+
+    /// CHECK: Emitter PDA of the pool, only used to sign the message.
+    #[account(
+        seeds = [wormhole::SEED_PREFIX_EMITTER, pool.key().as_ref()],
+        bump
+    )]
+    pub wormhole_emitter: AccountInfo<'info>,
+
+    /// CHECK: Sequence tracker of our emitter, created and incremented by the core bridge.
+    #[account(
+        mut,
+        seeds = [wormhole::SequenceTracker::SEED_PREFIX, wormhole_emitter.key().as_ref()],
+        bump,
+        seeds::program = wormhole_program.key()
+    )]
+    pub wormhole_sequence: AccountInfo<'info>,
+
+    // Fresh keypair the core bridge writes the message into
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    // System programs
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Handler function for burning LP tokens on Solana and asking the Sui pool to pay out.
+// The amounts are priced like `remove_liquidity`; nothing leaves the Solana vaults,
+// the payout is drawn from the remote reserves instead.
+pub fn handler(
+    ctx: Context<RequestRemoveLiquidityRemote>,
+    liquidity_amount: u64,       // Amount of LP tokens to burn
+    amount_a_min: u64,           // Minimum amount of token A user expects on Sui
+    amount_b_min: u64,           // Minimum amount of token B user expects on Sui
+    recipient_address: [u8; 32], // Sui address receiving the tokens
+) -> Result<()> {
+    msg!("Requesting remote liquidity removal...");
+    let clock = Clock::get()?;
+
+    // Withdrawals are allowed in every pool status and while the program is paused, like
+    // `remove_liquidity`, so LPs can always exit
+    // The funds are released by the linked pool
+    ctx.accounts.pool.require_linked()?;
+    require!(liquidity_amount > 0, ErrorCode::ZeroLiquidityBurned);
+    require!(recipient_address != [0u8; 32], ErrorCode::InvalidRemoteRecipient);
+
+    let before = ctx.accounts.pool.snapshot();
+
+    // Priced against the combined reserves, like `remove_liquidity`
+    let (reserve_a, reserve_b) = ctx.accounts.pool.combined_reserves()?;
+    let lp_supply = ctx.accounts.lp_mint.supply;
+
+    require!(lp_supply > 0, ErrorCode::PoolEmpty);
+    require!(liquidity_amount <= ctx.accounts.user_lp_token_account.amount, ErrorCode::InsufficientLpTokens);
+
+    let (amount_a, amount_b) =
+        quote_withdrawal(liquidity_amount, amount_a_min, amount_b_min, reserve_a, reserve_b, lp_supply)?;
+
+    // Burn user's LP tokens before anything is published
+    burn_lp_tokens(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.user_lp_token_account.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        liquidity_amount,
+    )?;
+
+    // The linked pool can only release what it holds for our LPs
+    let pool = &mut ctx.accounts.pool;
+    pool.debit_remote_reserves(amount_a, amount_b)?;
+    pool.total_liquidity = pool.total_liquidity.checked_sub(liquidity_amount).ok_or(ErrorCode::Underflow)?;
+    pool.last_updated_at = clock.unix_timestamp;
+
+    // The vaults are untouched, the payout comes out of the remote reserves
+    emit!(LiquidityRemoved {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        target_chain: pool.linked_chain_id,
+        amount_a,
+        amount_b,
+        lp_burned: liquidity_amount,
+        before,
+        after: pool.snapshot(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    let payload = BridgePayload::RemoveLiquidity(RemoveLiquidityPayload {
+        pool_id: pool.pool_id,
+        amount_a,
+        amount_b,
+        recipient_address,
+    });
+
+    let pool_address = pool.key().to_bytes();
+    let linked_chain_id = pool.linked_chain_id;
+    let bridge_fee = ctx.accounts.wormhole_bridge.fee();
+    OutboundMessage {
+        wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
+        wormhole_bridge: ctx.accounts.wormhole_bridge.to_account_info(),
+        wormhole_fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
+        wormhole_emitter: ctx.accounts.wormhole_emitter.to_account_info(),
+        wormhole_sequence: ctx.accounts.wormhole_sequence.to_account_info(),
+        wormhole_message: ctx.accounts.wormhole_message.to_account_info(),
+        payer: ctx.accounts.user.to_account_info(),
+        clock: ctx.accounts.clock.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .post(bridge_fee, ctx.bumps.wormhole_emitter, pool_address, linked_chain_id, payload)?;
+
+    msg!("Remote removal requested: A={}, B={}, LP={}", amount_a, amount_b, liquidity_amount);
+
+    Ok(())
+}
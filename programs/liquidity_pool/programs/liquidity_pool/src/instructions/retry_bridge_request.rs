@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::state::{BridgeRequest, BridgeStatus, Config, Pool, RateLimit};
+use crate::errors::ErrorCode;
+use crate::events::BridgeRequestStatusChanged;
+use crate::instructions::process_vaa::{apply_outcome, Outcome, PoolOperation};
+use crate::payloads::PayloadEnvelope;
+
+#[derive(Accounts)]
+pub struct RetryBridgeRequest<'info> {
+    // Anyone can retry a failed request, pays for missing recipient token accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Request recorded as Failed by `process_vaa`
+    #[account(
+        mut,
+        seeds = [
+            BridgeRequest::SEED,
+            &bridge_request.emitter_chain.to_le_bytes()[..],
+            bridge_request.emitter_address.as_ref(),
+            &bridge_request.wormhole_sequence.to_le_bytes()[..]
+        ],
+        bump = bridge_request.bump,
+        constraint = bridge_request.status == BridgeStatus::Failed @ ErrorCode::BridgeRequestNotFailed,
+        constraint = bridge_request.pool == pool.key() @ ErrorCode::PoolIdMismatch
+    )]
+    pub bridge_request: Box<Account<'info, BridgeRequest>>,
+
+    // The pool is still linked to the pool the message came from; a relink in between would
+    // credit the new counterpart with a deposit made on the old one
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump,
+        constraint = pool.linked_chain_id == bridge_request.emitter_chain @ ErrorCode::PoolLinkMismatch,
+        constraint = pool.linked_address == bridge_request.linked_address @ ErrorCode::PoolLinkMismatch
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Authority PDA, seeds checked. Signs mints and transfers.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump = pool.token_a_bump,
+        constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump = pool.token_b_bump,
+        constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint".as_ref(), pool.key().as_ref()],
+        bump = pool.lp_mint_bump,
+        constraint = lp_mint.key() == pool.lp_mint @ ErrorCode::InvalidMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Pool-owned LP account receiving the liquidity locked by the first deposit
+    #[account(
+        mut,
+        seeds = [b"locked_lp".as_ref(), pool.key().as_ref()],
+        bump = pool.locked_lp_bump
+    )]
+    pub locked_lp_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Same outflow caps as in ProcessVAA, the chain ones keyed by the request's source chain
+    /// CHECK: RateLimit PDA or empty, deserialized when the withdrawal is executed
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, pool.key().as_ref(), pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub pool_rate_limit_a: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized when the withdrawal is executed
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, pool.key().as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub pool_rate_limit_b: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized when the withdrawal is executed
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, &bridge_request.emitter_chain.to_le_bytes()[..], pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub chain_rate_limit_a: UncheckedAccount<'info>,
+
+    /// CHECK: RateLimit PDA or empty, deserialized when the withdrawal is executed
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED, &bridge_request.emitter_chain.to_le_bytes()[..], pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub chain_rate_limit_b: UncheckedAccount<'info>,
+
+    /// CHECK: Recipient named in the stored payload, checked when the operation is executed
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_a_program
+    )]
+    pub recipient_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_b_program
+    )]
+    pub recipient_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,   // LP mint's program
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Handler function for executing a failed bridge request again, e.g. once the pool is
+// unpaused or has the reserves. A retry that fails again reverts with the reason.
+pub fn handler(ctx: Context<RetryBridgeRequest>) -> Result<()> {
+    msg!("Retrying bridge request...");
+
+    // The stored payload was validated when the VAA was processed; `execute` checks again
+    // that it names this pool
+    let payload = PayloadEnvelope::decode(&ctx.accounts.bridge_request.payload)?.payload;
+
+    let outcome = PoolOperation {
+        pool: &mut ctx.accounts.pool,
+        pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        authority_bump: ctx.bumps.pool_authority,
+        token_a_mint: &ctx.accounts.token_a_mint,
+        token_b_mint: &ctx.accounts.token_b_mint,
+        token_a_account: &ctx.accounts.token_a_account,
+        token_b_account: &ctx.accounts.token_b_account,
+        lp_mint: &ctx.accounts.lp_mint,
+        recipient: ctx.accounts.recipient.key(),
+        recipient_lp_token_account: ctx.accounts.recipient_lp_token_account.to_account_info(),
+        locked_lp_account: ctx.accounts.locked_lp_account.to_account_info(),
+        recipient_token_a_account: ctx.accounts.recipient_token_a_account.to_account_info(),
+        recipient_token_b_account: ctx.accounts.recipient_token_b_account.to_account_info(),
+        rate_limits: [
+            ctx.accounts.pool_rate_limit_a.to_account_info(),
+            ctx.accounts.pool_rate_limit_b.to_account_info(),
+            ctx.accounts.chain_rate_limit_a.to_account_info(),
+            ctx.accounts.chain_rate_limit_b.to_account_info(),
+        ],
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_a_program: ctx.accounts.token_a_program.to_account_info(),
+        token_b_program: ctx.accounts.token_b_program.to_account_info(),
+        source_chain: ctx.accounts.bridge_request.emitter_chain,
+    }
+    .execute(payload)?;
+
+    if let Outcome::Failed(reason) = outcome {
+        msg!("Bridge request failed again: {:?}", reason);
+        return Err(reason.into());
+    }
+    let bridge_request = &mut ctx.accounts.bridge_request;
+    apply_outcome(bridge_request, outcome);
+    emit!(BridgeRequestStatusChanged::new(
+        bridge_request.key(),
+        bridge_request,
+        BridgeStatus::Failed,
+        Clock::get()?.unix_timestamp,
+    ));
+
+    msg!("Bridge request retried: status={:?}", ctx.accounts.bridge_request.status);
+
+    Ok(())
+}
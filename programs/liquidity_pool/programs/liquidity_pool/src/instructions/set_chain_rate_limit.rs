@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use wormhole_anchor_sdk::wormhole;
+use crate::state::{Config, RateLimit};
+use crate::errors::ErrorCode;
+use crate::events::ChainRateLimitSet;
+
+#[derive(Accounts)]
+#[instruction(chain: u16)]
+pub struct SetChainRateLimit<'info> {
+    // Program admin, pays for the rate limit account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // Outflow cap for this source chain and token, across all pools (PDA per chain ID and mint)
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RateLimit::SIZE,
+        seeds = [RateLimit::SEED, &chain.to_le_bytes()[..], mint.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Handler function for capping how much of a token messages from one chain can take out
+pub fn handler(
+    ctx: Context<SetChainRateLimit>,
+    chain: u16,
+    capacity: u64,
+    window_seconds: i64,
+    release_delay: i64,
+) -> Result<()> {
+    require!(
+        chain != 0 && chain != wormhole::CHAIN_ID_SOLANA,
+        ErrorCode::InvalidEmitterChain
+    );
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let rate_limit = &mut ctx.accounts.rate_limit;
+    rate_limit.configure(capacity, window_seconds, release_delay, timestamp)?;
+    rate_limit.mint = ctx.accounts.mint.key();
+    rate_limit.bump = ctx.bumps.rate_limit;
+
+    emit!(ChainRateLimitSet {
+        chain,
+        mint: rate_limit.mint,
+        capacity,
+        window_seconds,
+        release_delay,
+        timestamp,
+    });
+
+    msg!(
+        "Chain rate limit set: chain={}, mint={}, capacity={}, window={}s, delay={}s",
+        chain,
+        rate_limit.mint,
+        capacity,
+        window_seconds,
+        release_delay
+    );
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{Config, Pool, RateLimit};
+use crate::errors::ErrorCode;
+use crate::events::PoolRateLimitSet;
+
+#[derive(Accounts)]
+pub struct SetPoolRateLimit<'info> {
+    // Program admin, pays for the rate limit account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // One of the pool's tokens
+    #[account(
+        constraint = mint.key() == pool.token_a_mint || mint.key() == pool.token_b_mint @ ErrorCode::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // Outflow cap for this pool and token (PDA per pool and mint)
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RateLimit::SIZE,
+        seeds = [RateLimit::SEED, pool.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Handler function for capping how much of a token bridge messages can take out of a pool
+pub fn handler(
+    ctx: Context<SetPoolRateLimit>,
+    capacity: u64,
+    window_seconds: i64,
+    release_delay: i64,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let rate_limit = &mut ctx.accounts.rate_limit;
+    rate_limit.configure(capacity, window_seconds, release_delay, timestamp)?;
+    rate_limit.mint = ctx.accounts.mint.key();
+    rate_limit.bump = ctx.bumps.rate_limit;
+
+    emit!(PoolRateLimitSet {
+        pool: ctx.accounts.pool.key(),
+        mint: rate_limit.mint,
+        capacity,
+        window_seconds,
+        release_delay,
+        timestamp,
+    });
+
+    msg!(
+        "Pool rate limit set: pool={}, mint={}, capacity={}, window={}s, delay={}s",
+        ctx.accounts.pool.key(),
+        rate_limit.mint,
+        capacity,
+        window_seconds,
+        release_delay
+    );
+
+    Ok(())
+}
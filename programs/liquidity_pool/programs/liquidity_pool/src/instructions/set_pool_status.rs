@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, Pool, PoolStatus};
+use crate::errors::ErrorCode;
+use crate::events::PoolStatusChanged;
+
+#[derive(Accounts)]
+pub struct SetPoolStatus<'info> {
+    // Program admin
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+// Handler function for pausing, unpausing or deprecating a pool
+pub fn handler(ctx: Context<SetPoolStatus>, new_status: PoolStatus) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let clock = Clock::get()?;
+    let old_status = pool.status;
+
+    // Deprecation is final, and a no-op change is rejected
+    require!(old_status != PoolStatus::Deprecated, ErrorCode::InvalidPoolStatus);
+    require!(old_status != new_status, ErrorCode::InvalidPoolStatus);
+
+    pool.status = new_status;
+    pool.last_updated_at = clock.unix_timestamp;
+
+    emit!(PoolStatusChanged {
+        pool: pool.key(),
+        old_status,
+        new_status,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Pool status changed: {:?} -> {:?}", old_status, new_status);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, Pool};
+use crate::errors::ErrorCode;
+use crate::events::ProtocolFeeUpdated;
+
+#[derive(Accounts)]
+pub struct SetProtocolFee<'info> {
+    // Program admin, the only account allowed to reconfigure protocol fees
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+// Handler function for updating the protocol fee share and recipient
+pub fn handler(
+    ctx: Context<SetProtocolFee>,
+    protocol_fee_share: u64,
+    new_fee_recipient: Pubkey,
+) -> Result<()> {
+    require!(protocol_fee_share <= Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+
+    let pool = &mut ctx.accounts.pool;
+    let old_protocol_fee_share = pool.protocol_fee_share;
+    let old_fee_recipient = pool.fee_recipient;
+    pool.protocol_fee_share = protocol_fee_share;
+    pool.fee_recipient = new_fee_recipient;
+    pool.last_updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(ProtocolFeeUpdated {
+        pool: pool.key(),
+        old_protocol_fee_share,
+        new_protocol_fee_share: protocol_fee_share,
+        old_fee_recipient,
+        new_fee_recipient,
+        timestamp: pool.last_updated_at,
+    });
+
+    msg!("Protocol fee updated: share={} bps of fee, recipient={}", protocol_fee_share, new_fee_recipient);
+
+    Ok(())
+}
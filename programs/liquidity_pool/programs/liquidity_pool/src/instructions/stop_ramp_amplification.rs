@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, CurveType, Pool};
+use crate::errors::ErrorCode;
+use crate::events::AmplificationRamped;
+
+#[derive(Accounts)]
+pub struct StopRampAmplification<'info> {
+    // Program admin, the only account allowed to change a pool's amplification
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    // StableSwap pool state account
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump,
+        constraint = pool.curve_type == CurveType::StableSwap @ ErrorCode::NotStableSwapPool
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+// Handler function for freezing the amplification at its current value, ending any ramp.
+// Like starting a ramp, stopping one counts as the latest ramp start for `validate_ramp`.
+pub fn handler(ctx: Context<StopRampAmplification>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let current = pool.amplification(now);
+
+    pool.amp_initial = current;
+    pool.amp_target = current;
+    pool.amp_ramp_start = now;
+    pool.amp_ramp_end = now;
+    pool.last_updated_at = now;
+
+    emit!(AmplificationRamped {
+        pool: pool.key(),
+        initial_amplification: current,
+        target_amplification: current,
+        start: now,
+        end: now,
+    });
+
+    msg!("Amplification ramp stopped at {}", current);
+
+    Ok(())
+}
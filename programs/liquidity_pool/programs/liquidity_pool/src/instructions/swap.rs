@@ -0,0 +1,363 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Config, Oracle, Pool};
+use crate::errors::ErrorCode;
+use crate::events;
+use crate::curve::Curve;
+use crate::math::{mul_div_ceil, mul_div_floor};
+use crate::native_sol::{UserTokenAccount, WSOL_SEED};
+use crate::token_extensions::{amount_received, amount_to_send};
+use crate::instructions::add_liquidity::transfer_tokens;
+use crate::instructions::remove_liquidity::transfer_pool_tokens;
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    // User performing the swap
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Pool state account
+    #[account(
+        mut, // Protocol fees and last_updated_at are updated
+        seeds = [b"pool".as_ref(), pool.token_a_mint.key().as_ref(), pool.token_b_mint.key().as_ref(), &pool.fee_percentage.to_le_bytes()[..]],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Global config, checked for the pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool authority PDA
+    /// CHECK: Authority PDA, seeds checked below. Used as signer.
+    #[account(
+        seeds = [b"authority".as_ref(), pool.key().as_ref()],
+        bump // Bump needed for signing the output transfer
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    // Pool token mints, owned by SPL Token or Token-2022
+    #[account(address = pool.token_a_mint @ ErrorCode::InvalidMint, mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = pool.token_b_mint @ ErrorCode::InvalidMint, mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    // Pool's token accounts (the reserves)
+    #[account(
+        mut,
+        seeds = [b"token_a".as_ref(), pool.key().as_ref()],
+        bump = pool.token_a_bump,
+        constraint = token_a_account.key() == pool.token_a_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_a_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_b".as_ref(), pool.key().as_ref()],
+        bump = pool.token_b_bump,
+        constraint = token_b_account.key() == pool.token_b_account @ ErrorCode::InvalidPoolTokenAccount
+    )]
+    pub token_b_account: InterfaceAccount<'info, TokenAccount>,
+
+    // User's token accounts (one is the source, the other the destination depending on direction).
+    // Either can be left out on a native SOL side to pay with / receive lamports instead.
+    #[account(
+        mut,
+        constraint = user_token_a.mint == pool.token_a_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_a.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_a: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_b.mint == pool.token_b_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_b.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Temporary wSOL account standing in for the missing user token account, created and
+    // closed by the instruction (see `native_sol`)
+    /// CHECK: Uninitialized PDA, seeds checked below. Created as a token account in the handler.
+    #[account(
+        mut,
+        seeds = [WSOL_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_wsol_account: Option<UncheckedAccount<'info>>,
+
+    // Price oracle of the pool, gets an observation of the prices before the swap.
+    // Required once the pool has one (`pool.has_oracle`), so no swap can skip the ring buffer.
+    #[account(
+        mut,
+        seeds = [Oracle::SEED, pool.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+
+    pub token_a_program: Interface<'info, TokenInterface>, // Token A mint's program
+    pub token_b_program: Interface<'info, TokenInterface>, // Token B mint's program
+    pub system_program: Program<'info, System>,            // Creates the temporary wSOL account
+}
+
+// Result of pricing a swap against the pool's curve.
+// Amounts are what the pool receives and sends; token transfer fees come on top (see `execute_swap`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,    // Total amount taken from the user, fee included
+    pub amount_out: u64,   // Amount sent to the user
+    pub total_fee: u64,    // Fee charged on the input token
+    pub protocol_fee: u64, // Part of `total_fee` accrued to the protocol
+}
+
+// Handler function for an exact-input swap
+pub fn handler(
+    ctx: Context<Swap>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    a_to_b: bool,
+) -> Result<()> {
+    msg!("Swapping tokens (exact input)...");
+    require!(amount_in > 0, ErrorCode::ZeroSwapAmount);
+
+    let (reserve_in, reserve_out) = swap_reserves(ctx.accounts, a_to_b)?;
+    let (mint_in, mint_out) = swap_mints(ctx.accounts, a_to_b);
+    let curve = swap_curve(ctx.accounts, a_to_b)?;
+    let pool = &ctx.accounts.pool;
+
+    // The pool prices what reaches the vault; the minimum applies to what reaches the user
+    let amount_in_received = amount_received(mint_in, amount_in)?;
+    let quote = quote_exact_in(&curve, amount_in_received, reserve_in, reserve_out, pool.fee_percentage, pool.protocol_fee_share)?;
+    let amount_out_received = amount_received(mint_out, quote.amount_out)?;
+    require!(amount_out_received > 0, ErrorCode::ZeroSwapOutput);
+    require!(amount_out_received >= minimum_amount_out, ErrorCode::SlippageExceeded);
+
+    execute_swap(ctx.accounts, quote, a_to_b, &ctx.bumps)
+}
+
+// Current (input, output) reserves for the requested direction, excluding protocol fees
+pub fn swap_reserves(accounts: &Swap, a_to_b: bool) -> Result<(u64, u64)> {
+    // Check if pool is active
+    accounts.pool.require_active()?;
+
+    let (reserve_a, reserve_b) = accounts.pool.lp_reserves();
+    let (reserve_in, reserve_out) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+    require!(reserve_in > 0 && reserve_out > 0, ErrorCode::PoolEmpty);
+    Ok((reserve_in, reserve_out))
+}
+
+// Curve the pool currently prices with, oriented for the requested direction
+pub fn swap_curve(accounts: &Swap, a_to_b: bool) -> Result<Curve> {
+    let curve = accounts.pool.curve(Clock::get()?.unix_timestamp)?;
+    Ok(if a_to_b { curve } else { curve.reversed() })
+}
+
+// (input, output) mints for the requested direction
+pub fn swap_mints<'a, 'info>(
+    accounts: &'a Swap<'info>,
+    a_to_b: bool,
+) -> (&'a InterfaceAccount<'info, Mint>, &'a InterfaceAccount<'info, Mint>) {
+    if a_to_b {
+        (&accounts.token_a_mint, &accounts.token_b_mint)
+    } else {
+        (&accounts.token_b_mint, &accounts.token_a_mint)
+    }
+}
+
+// Move tokens for a priced swap and accrue the protocol fee; shared by both swap modes
+pub fn execute_swap(
+    accounts: &mut Swap,
+    quote: SwapQuote,
+    a_to_b: bool,
+    bumps: &SwapBumps,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // Price accumulators are weighted with the reserves held up to now
+    let observation = accounts.pool.accrue_prices(clock.unix_timestamp)?;
+    match accounts.oracle.as_mut() {
+        Some(oracle) => oracle.write(observation),
+        None => require!(!accounts.pool.has_oracle, ErrorCode::OracleRequired),
+    }
+
+    let before = accounts.pool.snapshot();
+
+    // Pick source/destination accounts for the requested direction
+    let user_token_a = UserTokenAccount::resolve(
+        accounts.user_token_a.as_ref(),
+        accounts.user_wsol_account.as_ref(),
+        bumps.user_wsol_account,
+        &accounts.token_a_mint,
+        &accounts.user,
+        &accounts.token_a_program,
+        &accounts.system_program,
+    )?;
+    let user_token_b = UserTokenAccount::resolve(
+        accounts.user_token_b.as_ref(),
+        accounts.user_wsol_account.as_ref(),
+        bumps.user_wsol_account,
+        &accounts.token_b_mint,
+        &accounts.user,
+        &accounts.token_b_program,
+        &accounts.system_program,
+    )?;
+    let (user_source, pool_destination, pool_source, user_destination) = if a_to_b {
+        (
+            &user_token_a,
+            accounts.token_a_account.to_account_info(),
+            accounts.token_b_account.to_account_info(),
+            &user_token_b,
+        )
+    } else {
+        (
+            &user_token_b,
+            accounts.token_b_account.to_account_info(),
+            accounts.token_a_account.to_account_info(),
+            &user_token_a,
+        )
+    };
+    let (program_in, program_out) = if a_to_b {
+        (accounts.token_a_program.to_account_info(), accounts.token_b_program.to_account_info())
+    } else {
+        (accounts.token_b_program.to_account_info(), accounts.token_a_program.to_account_info())
+    };
+    let (mint_in, mint_out) = swap_mints(accounts, a_to_b);
+    let amount_in_sent = amount_to_send(mint_in, quote.amount_in)?;
+
+    // Transfer the input from user to pool, grossed up so `quote.amount_in` arrives after any transfer fee
+    user_source.open(amount_in_sent)?;
+    transfer_tokens(
+        program_in,
+        user_source.account.clone(),
+        mint_in,
+        pool_destination,
+        accounts.user.to_account_info(),
+        amount_in_sent,
+    )?;
+    user_source.close()?;
+
+    // Transfer the output from pool to user, unwrapped right away when it is native SOL
+    user_destination.open(0)?;
+    transfer_pool_tokens(
+        program_out,
+        pool_source,
+        mint_out,
+        user_destination.account.clone(),
+        accounts.pool_authority.to_account_info(),
+        accounts.pool.key(),
+        quote.amount_out,
+        bumps.pool_authority,
+    )?;
+    user_destination.close()?;
+
+    // The protocol's cut of the fee stays in the vault but is excluded from the LP reserves
+    let pool = &mut accounts.pool;
+    let lp_amount_in = quote.amount_in.checked_sub(quote.protocol_fee).ok_or(ErrorCode::Underflow)?;
+    if a_to_b {
+        pool.protocol_fee_a = pool.protocol_fee_a.checked_add(quote.protocol_fee).ok_or(ErrorCode::Overflow)?;
+        pool.deposit_reserves(lp_amount_in, 0)?;
+        pool.withdraw_reserves(0, quote.amount_out)?;
+    } else {
+        pool.protocol_fee_b = pool.protocol_fee_b.checked_add(quote.protocol_fee).ok_or(ErrorCode::Overflow)?;
+        pool.deposit_reserves(0, lp_amount_in)?;
+        pool.withdraw_reserves(quote.amount_out, 0)?;
+    }
+    pool.last_updated_at = clock.unix_timestamp;
+
+    emit!(events::Swap {
+        pool: pool.key(),
+        user: accounts.user.key(),
+        a_to_b,
+        amount_in: quote.amount_in,
+        amount_out: quote.amount_out,
+        total_fee: quote.total_fee,
+        protocol_fee: quote.protocol_fee,
+        before,
+        after: pool.snapshot(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Swapped: in={}, out={}, fee={}, protocol_fee={}, a_to_b={}",
+        quote.amount_in,
+        quote.amount_out,
+        quote.total_fee,
+        quote.protocol_fee,
+        a_to_b
+    );
+
+    Ok(())
+}
+
+// Price an exact-input swap: the fee is taken from the input (rounded up) and the
+// remainder is swapped along the curve (output rounded down), both in the pool's favour.
+pub fn quote_exact_in(
+    curve: &Curve,
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_percentage: u64,
+    protocol_fee_share_bps: u64,
+) -> Result<SwapQuote> {
+    let total_fee = swap_fee(amount_in, fee_percentage)?;
+    let amount_in_after_fee = amount_in.checked_sub(total_fee).ok_or(ErrorCode::Underflow)?;
+
+    let amount_out = curve.amount_out(amount_in_after_fee, reserve_in, reserve_out)?;
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        total_fee,
+        protocol_fee: protocol_fee_share(total_fee, protocol_fee_share_bps)?,
+    })
+}
+
+// Price an exact-output swap: the input needed before fees is rounded up, then grossed up
+// for the fee (rounded up again) so the pool never gives out more than the curve allows.
+pub fn quote_exact_out(
+    curve: &Curve,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_percentage: u64,
+    protocol_fee_share_bps: u64,
+) -> Result<SwapQuote> {
+    require!(fee_percentage < Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+
+    // Rejects amounts the pool doesn't hold
+    let amount_in_after_fee = curve.amount_in(amount_out, reserve_in, reserve_out)?;
+
+    // amount_in = ceil(amount_in_after_fee * FEE_DENOMINATOR / (FEE_DENOMINATOR - fee))
+    let amount_in = mul_div_ceil(
+        amount_in_after_fee,
+        Pool::FEE_DENOMINATOR,
+        Pool::FEE_DENOMINATOR - fee_percentage,
+    )?;
+    let total_fee = amount_in
+        .checked_sub(amount_in_after_fee)
+        .ok_or(ErrorCode::Underflow)?;
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        total_fee,
+        protocol_fee: protocol_fee_share(total_fee, protocol_fee_share_bps)?,
+    })
+}
+
+// Fee owed on `amount_in`, rounded up
+pub fn swap_fee(amount_in: u64, fee_percentage: u64) -> Result<u64> {
+    require!(fee_percentage <= Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+    // fee <= amount_in because fee_percentage <= FEE_DENOMINATOR
+    mul_div_ceil(amount_in, fee_percentage, Pool::FEE_DENOMINATOR)
+}
+
+// Portion of a swap fee that is accrued to the protocol, rounded down
+pub fn protocol_fee_share(total_fee: u64, protocol_fee_share_bps: u64) -> Result<u64> {
+    require!(protocol_fee_share_bps <= Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+    mul_div_floor(total_fee, protocol_fee_share_bps, Pool::FEE_DENOMINATOR)
+}
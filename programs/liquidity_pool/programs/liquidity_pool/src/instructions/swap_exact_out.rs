@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::instructions::swap::{execute_swap, quote_exact_out, swap_curve, swap_mints, swap_reserves, Swap};
+use crate::token_extensions::amount_to_send;
+
+// Handler function for an exact-output swap (uses the same accounts as `swap`)
+pub fn handler(
+    ctx: Context<Swap>,
+    amount_out: u64,
+    maximum_amount_in: u64,
+    a_to_b: bool,
+) -> Result<()> {
+    msg!("Swapping tokens (exact output)...");
+    require!(amount_out > 0, ErrorCode::ZeroSwapOutput);
+
+    let (reserve_in, reserve_out) = swap_reserves(ctx.accounts, a_to_b)?;
+    let (mint_in, mint_out) = swap_mints(ctx.accounts, a_to_b);
+    let curve = swap_curve(ctx.accounts, a_to_b)?;
+    let pool = &ctx.accounts.pool;
+
+    // With transfer fees the pool sends enough for `amount_out` to arrive, and the maximum
+    // applies to what the user sends for the quoted input to reach the vault
+    let amount_out_sent = amount_to_send(mint_out, amount_out)?;
+    let quote = quote_exact_out(&curve, amount_out_sent, reserve_in, reserve_out, pool.fee_percentage, pool.protocol_fee_share)?;
+    require!(amount_to_send(mint_in, quote.amount_in)? <= maximum_amount_in, ErrorCode::SlippageExceeded);
+
+    execute_swap(ctx.accounts, quote, a_to_b, &ctx.bumps)
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, Pool};
+use crate::errors::ErrorCode;
+use crate::events::ConfigUpdated;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    // Current admin
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Handler function for updating the global config; `None` leaves a field unchanged
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    new_admin: Option<Pubkey>,
+    fee_tiers: Option<Vec<u64>>,
+    default_protocol_fee_share: Option<u64>,
+    paused: Option<bool>,
+) -> Result<()> {
+    msg!("Updating program config...");
+    let config = &mut ctx.accounts.config;
+
+    if let Some(fee_tiers) = fee_tiers {
+        Config::validate_fee_tiers(&fee_tiers)?;
+        config.fee_tiers = fee_tiers;
+    }
+    if let Some(default_protocol_fee_share) = default_protocol_fee_share {
+        require!(default_protocol_fee_share <= Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+        config.default_protocol_fee_share = default_protocol_fee_share;
+    }
+    if let Some(paused) = paused {
+        config.paused = paused;
+    }
+    if let Some(new_admin) = new_admin {
+        config.admin = new_admin;
+    }
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        fee_tiers: config.fee_tiers.clone(),
+        default_protocol_fee_share: config.default_protocol_fee_share,
+        paused: config.paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Config updated: admin={}, fee_tiers={:?}, default_protocol_fee_share={}, paused={}",
+        config.admin,
+        config.fee_tiers,
+        config.default_protocol_fee_share,
+        config.paused
+    );
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, ForeignEmitter};
+use crate::errors::ErrorCode;
+use crate::events::ForeignEmitterChanged;
+use crate::instructions::register_emitter::validate_emitter;
+
+#[derive(Accounts)]
+pub struct UpdateEmitter<'info> {
+    // Program admin
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [ForeignEmitter::SEED, &foreign_emitter.chain.to_le_bytes()[..]],
+        bump = foreign_emitter.bump
+    )]
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+}
+
+// Handler function for replacing the trusted emitter address of a chain
+pub fn handler(ctx: Context<UpdateEmitter>, address: [u8; 32]) -> Result<()> {
+    let foreign_emitter = &mut ctx.accounts.foreign_emitter;
+    validate_emitter(foreign_emitter.chain, &address)?;
+
+    let old_address = foreign_emitter.address;
+    foreign_emitter.address = address;
+
+    emit!(ForeignEmitterChanged {
+        chain: foreign_emitter.chain,
+        old_address,
+        new_address: address,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Updated emitter: chain={}, address={}", foreign_emitter.chain, hex::encode(address));
+
+    Ok(())
+}
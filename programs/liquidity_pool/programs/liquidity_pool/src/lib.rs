@@ -1,11 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, MintTo, Burn}; // Import necessary SPL token types
 
 // Declare modules within this crate
 pub mod instructions;
 pub mod state;
 pub mod errors;
 pub mod payloads; // Declare payloads module
+pub mod messaging;
+pub mod events;
+pub mod math;
+pub mod curve;
+pub mod token_extensions;
+pub mod native_sol;
 
 // Import modules created earlier (relative path from this file's perspective)
 // Note: Anchor build might handle paths differently, but typically modules are declared relative to lib.rs
@@ -13,8 +18,7 @@ pub mod payloads; // Declare payloads module
 // For now, assuming the build process correctly finds these modules within the workspace.
 // Use `crate::` to refer to modules within the same crate (program)
 use crate::instructions::*;
-use crate::state::*;
-use crate::errors::ErrorCode;
+use crate::state::{CurveType, PoolStatus, Twap};
 
 // Declare the program ID - Using the placeholder from README.md
 declare_id!("GL6uWvwZAapbf54GQb7PwKxXrC6gnjyNcrBMeAvkh7mg");
@@ -23,13 +27,34 @@ declare_id!("GL6uWvwZAapbf54GQb7PwKxXrC6gnjyNcrBMeAvkh7mg");
 pub mod liquidity_pool_program { // Renamed module to avoid conflict with crate name
     use super::*; // Make items from outer scope available
 
+    // Instruction: Create the global program config (upgrade authority only)
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        fee_tiers: Vec<u64>,             // Allowed pool fee percentages (basis points)
+        default_protocol_fee_share: u64  // Protocol share of swap fees for new pools (basis points of the fee)
+    ) -> Result<()> {
+        instructions::initialize_config::handler(ctx, fee_tiers, default_protocol_fee_share)
+    }
+
+    // Instruction: Update the global program config (admin only)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Option<Pubkey>,
+        fee_tiers: Option<Vec<u64>>,
+        default_protocol_fee_share: Option<u64>,
+        paused: Option<bool>
+    ) -> Result<()> {
+        instructions::update_config::handler(ctx, new_admin, fee_tiers, default_protocol_fee_share, paused)
+    }
+
     // Instruction: Create a new liquidity pool
     pub fn create_pool(
         ctx: Context<CreatePool>,
-        fee_percentage: u64,
-        pool_id: [u8; 32] // Unique ID matching Sui side
+        fee_percentage: u64,    // Fee tier (basis points); the pool ID is derived from it and the mints
+        curve_type: CurveType,  // Pricing curve, fixed for the pool's lifetime
+        amplification: u64      // StableSwap amplification coefficient (0 for constant-product)
     ) -> Result<()> {
-        instructions::create_pool::handler(ctx, fee_percentage, pool_id)
+        instructions::create_pool::handler(ctx, fee_percentage, curve_type, amplification)
     }
 
     // Instruction: Add liquidity to an existing pool
@@ -72,26 +97,200 @@ pub mod liquidity_pool_program { // Renamed module to avoid conflict with crate
         instructions::process_vaa::handler(ctx, vaa_hash)
     }
 
-    // TODO: Add other instructions as needed (e.g., swap, update_fees, etc.)
-}
+    // Instruction: Swap an exact amount of one pool token for the other
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,          // Exact amount of the input token to sell
+        minimum_amount_out: u64, // Minimum amount of the output token the user accepts
+        a_to_b: bool             // true: sell token A for token B, false: sell token B for token A
+    ) -> Result<()> {
+        instructions::swap::handler(ctx, amount_in, minimum_amount_out, a_to_b)
+    }
 
-// Optional: Define events if needed using #[event] macro
-/*
-#[event]
-pub struct LiquidityAdded {
-    pool: Pubkey,
-    user: Pubkey,
-    amount_a: u64,
-    amount_b: u64,
-    lp_tokens_minted: u64,
-}
+    // Instruction: Swap for an exact amount of one pool token, paying at most `maximum_amount_in`
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        amount_out: u64,         // Exact amount of the output token to receive
+        maximum_amount_in: u64,  // Maximum amount of the input token the user is willing to pay
+        a_to_b: bool             // true: sell token A for token B, false: sell token B for token A
+    ) -> Result<()> {
+        instructions::swap_exact_out::handler(ctx, amount_out, maximum_amount_in, a_to_b)
+    }
+
+    // Instruction: Transfer accrued protocol fees to the pool's fee recipient
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        instructions::collect_protocol_fees::handler(ctx)
+    }
+
+    // Instruction: Update the protocol's share of swap fees and the fee recipient
+    pub fn set_protocol_fee(
+        ctx: Context<SetProtocolFee>,
+        protocol_fee_share: u64, // Basis points of each swap fee accrued to the protocol
+        new_fee_recipient: Pubkey
+    ) -> Result<()> {
+        instructions::set_protocol_fee::handler(ctx, protocol_fee_share, new_fee_recipient)
+    }
+
+    // Instruction: Pause, unpause or deprecate a pool (admin only)
+    pub fn set_pool_status(
+        ctx: Context<SetPoolStatus>,
+        new_status: PoolStatus
+    ) -> Result<()> {
+        instructions::set_pool_status::handler(ctx, new_status)
+    }
+
+    // Instruction: Trust an emitter on a foreign chain (admin only)
+    pub fn register_emitter(
+        ctx: Context<RegisterEmitter>,
+        chain: u16,         // Wormhole chain ID
+        address: [u8; 32]   // Emitter address (Wormhole format)
+    ) -> Result<()> {
+        instructions::register_emitter::handler(ctx, chain, address)
+    }
+
+    // Instruction: Replace the trusted emitter address for a chain (admin only)
+    pub fn update_emitter(
+        ctx: Context<UpdateEmitter>,
+        address: [u8; 32]
+    ) -> Result<()> {
+        instructions::update_emitter::handler(ctx, address)
+    }
+
+    // Instruction: Stop trusting a foreign chain (admin only)
+    pub fn remove_emitter(ctx: Context<RemoveEmitter>) -> Result<()> {
+        instructions::remove_emitter::handler(ctx)
+    }
+
+    // Instruction: Add liquidity on Solana and publish the deposit to the Sui pool
+    pub fn bridge_add_liquidity(
+        ctx: Context<BridgeAddLiquidity>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64
+    ) -> Result<()> {
+        instructions::bridge_add_liquidity::handler(
+            ctx,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min
+        )
+    }
+
+    // Instruction: Burn LP tokens on Solana and ask the Sui pool to release the funds
+    pub fn request_remove_liquidity_remote(
+        ctx: Context<RequestRemoveLiquidityRemote>,
+        liquidity_amount: u64,      // Amount of LP tokens to burn
+        amount_a_min: u64,
+        amount_b_min: u64,
+        recipient_address: [u8; 32] // Sui address receiving the tokens
+    ) -> Result<()> {
+        instructions::request_remove_liquidity_remote::handler(
+            ctx,
+            liquidity_amount,
+            amount_a_min,
+            amount_b_min,
+            recipient_address
+        )
+    }
+
+    // Instruction: Create a pool linked to a remote pool from a factory's CREATE_POOL VAA
+    pub fn create_pool_from_vaa(
+        ctx: Context<CreatePoolFromVaa>,
+        vaa_hash: [u8; 32],  // Identifier for the VAA to process
+        fee_percentage: u64  // Fee tier from the VAA, needed up front for the pool seeds
+    ) -> Result<()> {
+        instructions::create_pool_from_vaa::handler(ctx, vaa_hash, fee_percentage)
+    }
+
+    // Instruction: Link a pool to its counterpart on another chain and publish LINK_POOL (admin only)
+    pub fn link_pool(
+        ctx: Context<LinkPool>,
+        remote_chain: u16,        // Wormhole chain ID of the remote pool
+        remote_address: [u8; 32]  // Remote pool address, left-padded to 32 bytes
+    ) -> Result<()> {
+        instructions::link_pool::handler(ctx, remote_chain, remote_address)
+    }
+
+    // Instruction: Cap bridge-driven outflow of one pool token (admin only)
+    pub fn set_pool_rate_limit(
+        ctx: Context<SetPoolRateLimit>,
+        capacity: u64,       // Maximum outflow per window
+        window_seconds: i64, // Rolling window length
+        release_delay: i64   // Cooldown before an over-limit withdrawal can be released
+    ) -> Result<()> {
+        instructions::set_pool_rate_limit::handler(ctx, capacity, window_seconds, release_delay)
+    }
+
+    // Instruction: Cap outflow of a token driven by messages from one chain (admin only)
+    pub fn set_chain_rate_limit(
+        ctx: Context<SetChainRateLimit>,
+        chain: u16,          // Wormhole chain ID the cap applies to
+        capacity: u64,       // Maximum outflow per window
+        window_seconds: i64, // Rolling window length
+        release_delay: i64   // Cooldown before an over-limit withdrawal can be released
+    ) -> Result<()> {
+        instructions::set_chain_rate_limit::handler(ctx, chain, capacity, window_seconds, release_delay)
+    }
+
+    // Instruction: Pay out a withdrawal delayed by a rate limit once its cooldown is over
+    pub fn release_bridge_request(ctx: Context<ReleaseBridgeRequest>) -> Result<()> {
+        instructions::release_bridge_request::handler(ctx)
+    }
+
+    // Instruction: Drop a withdrawal delayed by a rate limit (admin only)
+    pub fn cancel_bridge_request(ctx: Context<CancelBridgeRequest>) -> Result<()> {
+        instructions::cancel_bridge_request::handler(ctx)
+    }
+
+    // Instruction: Execute a failed bridge request again
+    pub fn retry_bridge_request(ctx: Context<RetryBridgeRequest>) -> Result<()> {
+        instructions::retry_bridge_request::handler(ctx)
+    }
+
+    // Instruction: Return a failed remote deposit to the source chain (deposit recipient only)
+    pub fn refund_bridge_request(
+        ctx: Context<RefundBridgeRequest>,
+        refund_address: [u8; 32] // Address on the source chain receiving the tokens
+    ) -> Result<()> {
+        instructions::refund_bridge_request::handler(ctx, refund_address)
+    }
+
+    // Instruction: Create a pool's price observation ring buffer
+    pub fn initialize_oracle(
+        ctx: Context<InitializeOracle>,
+        capacity: u16 // Number of observations kept (1-250)
+    ) -> Result<()> {
+        instructions::initialize_oracle::handler(ctx, capacity)
+    }
+
+    // Instruction: Append a price observation to a pool's oracle
+    pub fn record_observation(ctx: Context<RecordObservation>) -> Result<()> {
+        instructions::record_observation::handler(ctx)
+    }
+
+    // Instruction: Return time-weighted average prices through return data (read-only)
+    pub fn observe(
+        ctx: Context<Observe>,
+        seconds_ago: Vec<u32> // Window lengths, each ending now
+    ) -> Result<Vec<Twap>> {
+        instructions::observe::handler(ctx, seconds_ago)
+    }
+
+    // Instruction: Ramp a StableSwap pool's amplification towards a new value (admin only)
+    pub fn ramp_amplification(
+        ctx: Context<RampAmplification>,
+        target_amplification: u64, // Amplification reached at the end of the ramp
+        ramp_end: i64              // Unix timestamp the ramp ends at, at least a day away
+    ) -> Result<()> {
+        instructions::ramp_amplification::handler(ctx, target_amplification, ramp_end)
+    }
+
+    // Instruction: Freeze a StableSwap pool's amplification at its current value (admin only)
+    pub fn stop_ramp_amplification(ctx: Context<StopRampAmplification>) -> Result<()> {
+        instructions::stop_ramp_amplification::handler(ctx)
+    }
 
-#[event]
-pub struct LiquidityRemoved {
-    pool: Pubkey,
-    user: Pubkey,
-    amount_a: u64,
-    amount_b: u64,
-    lp_tokens_burned: u64,
+    // TODO: Add other instructions as needed (e.g., update_fees, etc.)
 }
-*/
@@ -0,0 +1,293 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+// Shared token amount arithmetic.
+// Products of two u64 amounts are taken in u128, where they can't overflow; a result that
+// doesn't fit back into u64 is an `Overflow` error and a zero denominator a `DivisionByZero`
+// error, so no input makes the program panic. The StableSwap solver works on u128 balances,
+// whose products are taken in 256 bits (a (high, low) pair of u128) the same way.
+
+// floor(a * b / denominator)
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::DivisionByZero);
+    let quotient = a as u128 * b as u128 / denominator as u128;
+    u64::try_from(quotient).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// ceil(a * b / denominator)
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::DivisionByZero);
+    let product = a as u128 * b as u128;
+    let quotient = product.div_ceil(denominator as u128);
+    u64::try_from(quotient).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// floor(sqrt(a * b)), the geometric mean of two amounts; always fits in u64
+pub fn sqrt_product(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    if product < 2 {
+        return product as u64;
+    }
+    // Newton's method from a power of two at or above the root, decreasing until it settles
+    let mut root = 1u128 << (128 - product.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (root + product / root) / 2;
+        if next >= root {
+            return root as u64;
+        }
+        root = next;
+    }
+}
+
+// floor((a * b + c * d) / denominator) for u128 operands
+pub fn sum_of_products_div(a: u128, b: u128, c: u128, d: u128, denominator: u128) -> Result<u128> {
+    require!(denominator > 0, ErrorCode::DivisionByZero);
+    let sum = a.checked_mul(b).zip(c.checked_mul(d)).and_then(|(ab, cd)| ab.checked_add(cd));
+    if let Some(numerator) = sum {
+        return Ok(numerator / denominator);
+    }
+    let (high_ab, low_ab) = widening_mul(a, b);
+    let (high_cd, low_cd) = widening_mul(c, d);
+    let (low, carry) = low_ab.overflowing_add(low_cd);
+    let high = high_ab
+        .checked_add(high_cd)
+        .and_then(|high| high.checked_add(carry as u128))
+        .ok_or(ErrorCode::Overflow)?;
+    div_wide(high, low, denominator).ok_or(error!(ErrorCode::Overflow))
+}
+
+// floor(a * b / denominator) for u128 operands
+pub fn mul_div_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    sum_of_products_div(a, b, 0, 0, denominator)
+}
+
+// Full 256-bit product of two u128, as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    // Sum of three values below 2^64 each, can't overflow
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+// (high * 2^128 + low) / divisor, or None when the quotient doesn't fit in u128
+fn div_wide(high: u128, low: u128, divisor: u128) -> Option<u128> {
+    if high >= divisor {
+        return None;
+    }
+    // Long division one bit at a time; the remainder stays below the divisor, so when the shift
+    // carries a bit out of it the true value is above the divisor and the wrapping subtraction exact
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{Curve, StableSwap};
+    use crate::instructions::add_liquidity::quote_deposit;
+    use crate::instructions::remove_liquidity::quote_withdrawal;
+    use crate::instructions::swap::{quote_exact_in, quote_exact_out};
+
+    // Deterministic xorshift64* generator, biased towards boundary values and
+    // spread over every magnitude so both tiny and near-u64::MAX inputs are hit
+    struct Inputs(u64);
+
+    impl Inputs {
+        const EDGES: [u64; 8] = [0, 1, 2, 10_000, u32::MAX as u64, 1 << 63, u64::MAX - 1, u64::MAX];
+
+        fn next_raw(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn next(&mut self) -> u64 {
+            let raw = self.next_raw();
+            match raw % 8 {
+                0 => Self::EDGES[(raw >> 3) as usize % Self::EDGES.len()],
+                _ => self.next_raw() >> ((raw >> 3) % 64),
+            }
+        }
+    }
+
+    const SAMPLES: usize = 200_000;
+
+    #[test]
+    fn mul_div_floor_rounds_down_or_reports_overflow() {
+        let mut inputs = Inputs(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..SAMPLES {
+            let (a, b, d) = (inputs.next(), inputs.next(), inputs.next());
+            let product = a as u128 * b as u128;
+            match mul_div_floor(a, b, d) {
+                Ok(q) => {
+                    let (q, d) = (q as u128, d as u128);
+                    assert!(q * d <= product && product < (q + 1) * d, "floor({a} * {b} / {d}) = {q}");
+                }
+                Err(err) if d == 0 => assert_eq!(err, ErrorCode::DivisionByZero.into()),
+                Err(err) => {
+                    assert_eq!(err, ErrorCode::Overflow.into());
+                    assert!(product / d as u128 > u64::MAX as u128);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mul_div_ceil_rounds_up_or_reports_overflow() {
+        let mut inputs = Inputs(0xD1B5_4A32_D192_ED03);
+        for _ in 0..SAMPLES {
+            let (a, b, d) = (inputs.next(), inputs.next(), inputs.next());
+            let product = a as u128 * b as u128;
+            match mul_div_ceil(a, b, d) {
+                Ok(q) => {
+                    let (q, d) = (q as u128, d as u128);
+                    assert!(q * d >= product && (q == 0 || (q - 1) * d < product), "ceil({a} * {b} / {d}) = {q}");
+                }
+                Err(err) if d == 0 => assert_eq!(err, ErrorCode::DivisionByZero.into()),
+                Err(err) => {
+                    assert_eq!(err, ErrorCode::Overflow.into());
+                    assert!(product.div_ceil(d as u128) > u64::MAX as u128);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_results_match_and_overflow_is_an_error() {
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(mul_div_floor(7, 3, 2).unwrap(), 10);
+        assert_eq!(mul_div_ceil(7, 3, 2).unwrap(), 11);
+        assert_eq!(mul_div_ceil(0, 3, 2).unwrap(), 0);
+        assert_eq!(mul_div_floor(u64::MAX, 2, 1).unwrap_err(), ErrorCode::Overflow.into());
+        assert_eq!(mul_div_ceil(1, 1, 0).unwrap_err(), ErrorCode::DivisionByZero.into());
+    }
+
+    #[test]
+    fn sqrt_product_is_the_floor_of_the_root() {
+        let mut inputs = Inputs(0xA076_1D64_78BD_642F);
+        for _ in 0..SAMPLES {
+            let (a, b) = (inputs.next(), inputs.next());
+            let product = a as u128 * b as u128;
+            let root = sqrt_product(a, b) as u128;
+            assert!(root * root <= product, "sqrt({a} * {b}) = {root} is too large");
+            if let Some(square) = (root + 1).checked_mul(root + 1) {
+                assert!(square > product, "sqrt({a} * {b}) = {root} is too small");
+            }
+        }
+        assert_eq!(sqrt_product(u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(sqrt_product(1_000_000, 4_000_000), 2_000_000);
+        assert_eq!(sqrt_product(0, u64::MAX), 0);
+    }
+
+    // u128 spread over every magnitude, like `Inputs::next`
+    fn next_u128(inputs: &mut Inputs) -> u128 {
+        let wide = (inputs.next() as u128) << 64 | inputs.next() as u128;
+        wide >> (inputs.next_raw() % 128)
+    }
+
+    #[test]
+    fn widening_mul_matches_u128_and_the_largest_product() {
+        let mut inputs = Inputs(0x5851_F42D_4C95_7F2D);
+        for _ in 0..SAMPLES {
+            let (a, b) = (inputs.next() as u128, inputs.next() as u128);
+            assert_eq!(widening_mul(a, b), (0, a * b));
+        }
+        // (2^128 - 1)^2 = (2^128 - 2) * 2^128 + 1
+        assert_eq!(widening_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(widening_mul(1 << 64, 1 << 64), (1, 0));
+    }
+
+    #[test]
+    fn sum_of_products_div_rounds_down_or_reports_overflow() {
+        let mut inputs = Inputs(0x1405_7B7E_F767_814F);
+        for _ in 0..SAMPLES {
+            let (a, b) = (next_u128(&mut inputs), next_u128(&mut inputs));
+            let (c, d) = (next_u128(&mut inputs), next_u128(&mut inputs));
+            let denominator = next_u128(&mut inputs);
+            let (high_ab, low_ab) = widening_mul(a, b);
+            let (high_cd, low_cd) = widening_mul(c, d);
+            let (low, carry) = low_ab.overflowing_add(low_cd);
+            let high = high_ab.checked_add(high_cd).and_then(|high| high.checked_add(carry as u128));
+            match sum_of_products_div(a, b, c, d, denominator) {
+                Ok(q) => {
+                    // q * denominator <= numerator < q * denominator + denominator
+                    let numerator = (high.unwrap(), low);
+                    let (high, low) = widening_mul(q, denominator);
+                    assert!((high, low) <= numerator, "({a} * {b} + {c} * {d}) / {denominator} = {q} is too large");
+                    let (next_low, carry) = low.overflowing_add(denominator);
+                    assert!((high + carry as u128, next_low) > numerator, "({a} * {b} + {c} * {d}) / {denominator} = {q} is too small");
+                }
+                Err(err) if denominator == 0 => assert_eq!(err, ErrorCode::DivisionByZero.into()),
+                Err(err) => {
+                    assert_eq!(err, ErrorCode::Overflow.into());
+                    assert!(!matches!(high, Some(high) if high < denominator));
+                }
+            }
+        }
+        assert_eq!(mul_div_u128(u128::MAX, u128::MAX, u128::MAX).unwrap(), u128::MAX);
+        assert_eq!(sum_of_products_div(u128::MAX, 2, 2, 1, 4).unwrap(), u128::MAX / 2 + 1);
+        assert_eq!(mul_div_u128(u128::MAX, 2, 1).unwrap_err(), ErrorCode::Overflow.into());
+        assert_eq!(sum_of_products_div(u128::MAX, u128::MAX, u128::MAX, u128::MAX, u128::MAX).unwrap_err(), ErrorCode::Overflow.into());
+    }
+
+    // The old u64 `checked_mul(..).unwrap()` panicked on these; now they price or return an error.
+    // One in four samples prices on a StableSwap curve with random amplification and decimals.
+    #[test]
+    fn quotes_never_panic_and_favour_the_pool() {
+        let mut inputs = Inputs(0x0123_4567_89AB_CDEF);
+        for _ in 0..SAMPLES / 4 {
+            let (amount_a, amount_b) = (inputs.next(), inputs.next());
+            let (reserve_a, reserve_b, lp_supply) = (inputs.next(), inputs.next(), inputs.next());
+            let curve = match inputs.next_raw() % 4 {
+                0 => Curve::StableSwap(
+                    StableSwap::new(inputs.next_raw() % 10_000 + 1, inputs.next_raw() as u8 % 10, inputs.next_raw() as u8 % 10).unwrap(),
+                ),
+                _ => Curve::ConstantProduct,
+            };
+
+            if let Ok((a, b, minted)) = quote_deposit(&curve, amount_a, amount_b, 0, 0, reserve_a, reserve_b, lp_supply) {
+                assert!(a <= amount_a && b <= amount_b);
+                if lp_supply > 0 {
+                    // Never more LP than the deposited share of either reserve
+                    assert!(minted as u128 * reserve_a as u128 <= a as u128 * lp_supply as u128);
+                    assert!(minted as u128 * reserve_b as u128 <= b as u128 * lp_supply as u128);
+                }
+            }
+
+            let liquidity = inputs.next().min(lp_supply);
+            if let Ok((a, b)) = quote_withdrawal(liquidity, 0, 0, reserve_a, reserve_b, lp_supply) {
+                assert!(a <= reserve_a && b <= reserve_b);
+            }
+
+            let fee = inputs.next() % 10_001;
+            if let Ok(quote) = quote_exact_in(&curve, amount_a, reserve_a, reserve_b, fee, 2_000) {
+                assert!(quote.amount_out <= reserve_b);
+                assert!(reserve_a == 0 || quote.amount_out < reserve_b.max(1));
+                assert!(quote.protocol_fee <= quote.total_fee && quote.total_fee <= quote.amount_in);
+            }
+            if let Ok(quote) = quote_exact_out(&curve, amount_b, reserve_a, reserve_b, fee, 2_000) {
+                assert!(quote.amount_out < reserve_b);
+                assert!(quote.protocol_fee <= quote.total_fee && quote.total_fee <= quote.amount_in);
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use wormhole_anchor_sdk::wormhole;
+use crate::payloads::{BridgePayload, PayloadEnvelope};
+
+// Accounts needed to publish a message through the Wormhole core bridge.
+// Instructions that talk to Sui declare these accounts and hand them over here.
+pub struct OutboundMessage<'info> {
+    pub wormhole_program: AccountInfo<'info>,
+    pub wormhole_bridge: AccountInfo<'info>,        // Core bridge config, holds the message fee
+    pub wormhole_fee_collector: AccountInfo<'info>, // Receives the message fee
+    pub wormhole_emitter: AccountInfo<'info>,       // Emitter PDA of the sending pool, signs the message
+    pub wormhole_sequence: AccountInfo<'info>,      // Sequence tracker of that emitter (created by the core bridge)
+    pub wormhole_message: AccountInfo<'info>,       // Fresh signer account the message is written to
+    pub payer: AccountInfo<'info>,
+    pub clock: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> OutboundMessage<'info> {
+    // Pay the bridge fee and post `payload` from the emitter of the `source_address` pool; returns
+    // the message sequence, which doubles as the envelope nonce. Each pool has its own emitter
+    // (`[SEED_PREFIX_EMITTER, pool]`), so the VAA's emitter address identifies the sending pool
+    // even in version 1 payloads, which carry no sender.
+    pub fn post(
+        self,
+        bridge_fee: u64,
+        emitter_bump: u8,
+        source_address: [u8; 32],
+        target_chain: u16,
+        payload: BridgePayload,
+    ) -> Result<u64> {
+        // The tracker doesn't exist until the first message, which gets sequence 0
+        let sequence = if self.wormhole_sequence.data_is_empty() {
+            0
+        } else {
+            wormhole::SequenceTracker::try_deserialize(&mut &self.wormhole_sequence.try_borrow_data()?[..])?.value()
+        };
+
+        let payload = PayloadEnvelope::outbound(payload, source_address, target_chain, sequence).encode()?;
+
+        if bridge_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.clone(),
+                    Transfer {
+                        from: self.payer.clone(),
+                        to: self.wormhole_fee_collector.clone(),
+                    },
+                ),
+                bridge_fee,
+            )?;
+        }
+
+        let seeds = &[wormhole::SEED_PREFIX_EMITTER.as_ref(), source_address.as_ref(), &[emitter_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        wormhole::post_message(
+            CpiContext::new_with_signer(
+                self.wormhole_program,
+                wormhole::PostMessage {
+                    config: self.wormhole_bridge,
+                    message: self.wormhole_message,
+                    emitter: self.wormhole_emitter,
+                    sequence: self.wormhole_sequence,
+                    payer: self.payer,
+                    fee_collector: self.wormhole_fee_collector,
+                    clock: self.clock,
+                    rent: self.rent,
+                    system_program: self.system_program,
+                },
+                signer_seeds,
+            ),
+            0, // batch_id, messages are not batched
+            payload,
+            wormhole::Finality::Finalized,
+        )?;
+
+        msg!("Posted Wormhole message, sequence={}", sequence);
+        Ok(sequence)
+    }
+}
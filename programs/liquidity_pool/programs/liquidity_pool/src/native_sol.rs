@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::{
+    self, CloseAccount, InitializeAccount3, Mint, SyncNative, TokenAccount, TokenInterface,
+};
+use crate::errors::ErrorCode;
+
+// Native SOL.
+// Pools hold SOL as wrapped SOL (the SPL Token native mint) like any other token. So users don't
+// have to wrap and unwrap it themselves, the liquidity and swap instructions accept a temporary
+// wSOL account instead of the user's token account on the native side: it is created and funded
+// with lamports from the user, used for the transfer and closed back to the user, all within the
+// same instruction.
+
+// Seed of the temporary wSOL account, per user: [WSOL_SEED, user]
+pub const WSOL_SEED: &[u8] = b"wsol";
+
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID
+}
+
+// The user's side of a transfer: their own token account, or a temporary wSOL account
+pub struct UserTokenAccount<'info> {
+    pub account: AccountInfo<'info>,
+    wsol: Option<TemporaryWsol<'info>>,
+}
+
+struct TemporaryWsol<'info> {
+    user: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    bump: u8,
+}
+
+impl<'info> UserTokenAccount<'info> {
+    // Pick the account for one side of the pool. A token account passed by the user always wins;
+    // without one the side has to be native SOL and the wSOL account has to be passed instead.
+    pub fn resolve(
+        token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        wsol_account: Option<&UncheckedAccount<'info>>,
+        wsol_bump: Option<u8>,
+        mint: &InterfaceAccount<'info, Mint>,
+        user: &Signer<'info>,
+        token_program: &Interface<'info, TokenInterface>,
+        system_program: &Program<'info, System>,
+    ) -> Result<Self> {
+        if let Some(token_account) = token_account {
+            return Ok(Self { account: token_account.to_account_info(), wsol: None });
+        }
+        require!(is_native_mint(&mint.key()), ErrorCode::MissingUserTokenAccount);
+        let (Some(wsol_account), Some(bump)) = (wsol_account, wsol_bump) else {
+            return err!(ErrorCode::MissingUserTokenAccount);
+        };
+        Ok(Self {
+            account: wsol_account.to_account_info(),
+            wsol: Some(TemporaryWsol {
+                user: user.to_account_info(),
+                mint: mint.to_account_info(),
+                token_program: token_program.to_account_info(),
+                system_program: system_program.to_account_info(),
+                bump,
+            }),
+        })
+    }
+
+    // Create the wSOL account and wrap `lamports` into it (0 when it only receives).
+    // Nothing to do for a regular token account.
+    pub fn open(&self, lamports: u64) -> Result<()> {
+        let Some(wsol) = &self.wsol else {
+            return Ok(());
+        };
+        let user_key = wsol.user.key();
+        let seeds = &[WSOL_SEED, user_key.as_ref(), &[wsol.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let space = spl_token::state::Account::LEN;
+        let rent = Rent::get()?.minimum_balance(space);
+        let current_lamports = self.account.lamports();
+        if current_lamports == 0 {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    wsol.system_program.clone(),
+                    CreateAccount { from: wsol.user.clone(), to: self.account.clone() },
+                    signer_seeds,
+                ),
+                rent,
+                space as u64,
+                wsol.token_program.key,
+            )?;
+        } else {
+            // Like Anchor's `init`: someone may have sent lamports to the address already,
+            // `create_account` would fail on it
+            let top_up = rent.saturating_sub(current_lamports);
+            if top_up > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        wsol.system_program.clone(),
+                        Transfer { from: wsol.user.clone(), to: self.account.clone() },
+                    ),
+                    top_up,
+                )?;
+            }
+            system_program::allocate(
+                CpiContext::new_with_signer(
+                    wsol.system_program.clone(),
+                    Allocate { account_to_allocate: self.account.clone() },
+                    signer_seeds,
+                ),
+                space as u64,
+            )?;
+            system_program::assign(
+                CpiContext::new_with_signer(
+                    wsol.system_program.clone(),
+                    Assign { account_to_assign: self.account.clone() },
+                    signer_seeds,
+                ),
+                wsol.token_program.key,
+            )?;
+        }
+
+        // Owned by the user, so they sign the transfer out of it and the close
+        token_interface::initialize_account3(CpiContext::new(
+            wsol.token_program.clone(),
+            InitializeAccount3 {
+                account: self.account.clone(),
+                mint: wsol.mint.clone(),
+                authority: wsol.user.clone(),
+            },
+        ))?;
+
+        if lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    wsol.system_program.clone(),
+                    Transfer { from: wsol.user.clone(), to: self.account.clone() },
+                ),
+                lamports,
+            )?;
+            token_interface::sync_native(CpiContext::new(
+                wsol.token_program.clone(),
+                SyncNative { account: self.account.clone() },
+            ))?;
+        }
+        Ok(())
+    }
+
+    // Close the wSOL account, unwrapping whatever it holds and its rent back to the user.
+    // Nothing to do for a regular token account.
+    pub fn close(&self) -> Result<()> {
+        let Some(wsol) = &self.wsol else {
+            return Ok(());
+        };
+        token_interface::close_account(CpiContext::new(
+            wsol.token_program.clone(),
+            CloseAccount {
+                account: self.account.clone(),
+                destination: wsol.user.clone(),
+                authority: wsol.user.clone(),
+            },
+        ))
+    }
+}
@@ -1,32 +1,574 @@
 use anchor_lang::prelude::*;
 use borsh::{BorshDeserialize, BorshSerialize};
+use std::io::Read;
+use crate::errors::ErrorCode;
 
-// Matches the BridgeOperation enum/struct on the Sui side (adjust fields as needed)
-// We only need Deserialize here as we are receiving this from Sui via Wormhole
-#[derive(BorshDeserialize, BorshSerialize, Debug)]
+// Wire format shared with the Sui package (`omnisphere_sui::types` / `bridge_interface`).
+// A body is `[op_code u8][fields]` with fixed-size fields: integers are big-endian and
+// addresses are 32 bytes, the same way Sui writes and reads them.
+//
+// Version 1 is the bare body, which is what Sui emits and accepts today. Version 2 wraps
+// the body in a `PayloadEnvelope` header. Receivers accept every known version so senders
+// can switch once the other side is upgraded.
+pub const PROTOCOL_VERSION_1: u8 = 1;
+pub const PROTOCOL_VERSION_2: u8 = 2;
+
+// Version used for messages we publish; Sui only reads bare bodies so far
+pub const OUTBOUND_PROTOCOL_VERSION: u8 = PROTOCOL_VERSION_1;
+
+// Marks an enveloped payload. 'O' is not a valid op code, so bare bodies never start with it.
+pub const PAYLOAD_MAGIC: [u8; 4] = *b"OMNI";
+
+// Wormhole chain ID of Sui
+pub const CHAIN_ID_SUI: u16 = 21;
+
+// Raw payload bytes of a posted VAA, used as the message type of `wormhole::PostedVaa`.
+// The core bridge already strips the length prefix, so this takes everything that remains.
+#[derive(BorshSerialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawPayload(pub Vec<u8>);
+
+impl BorshDeserialize for RawPayload {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(RawPayload(bytes))
+    }
+}
+
+// Operation codes, matching the OPERATION_* constants in Sui's `types.move`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
 pub enum BridgeOperationCode {
-    AddLiquidityCompletion = 0, // Example: Corresponds to completing AddLiquidity on Solana
-    RemoveLiquidityCompletion = 1, // Example: Corresponds to completing RemoveLiquidity on Solana
-    // Add other operation codes defined in your cross-chain protocol
+    CreatePoolMirror = 0, // Reserved on both chains, no body defined yet
+    AddLiquidity = 1,
+    RemoveLiquidity = 2,
+    LinkPool = 3,
+    CreatePool = 4,
+}
+
+impl TryFrom<u8> for BridgeOperationCode {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(BridgeOperationCode::CreatePoolMirror),
+            1 => Ok(BridgeOperationCode::AddLiquidity),
+            2 => Ok(BridgeOperationCode::RemoveLiquidity),
+            3 => Ok(BridgeOperationCode::LinkPool),
+            4 => Ok(BridgeOperationCode::CreatePool),
+            _ => err!(ErrorCode::InvalidBridgeOperation),
+        }
+    }
+}
+
+// ADD_LIQUIDITY: `[1][pool_id 32][amount_a u64][amount_b u64]`, optionally followed by
+// `[recipient 32]`. Sui consumes the 49-byte form to mirror a deposit; a deposit made on Sui
+// carries the Solana account that receives the LP tokens.
+//
+// `pool_id` in liquidity bodies is the `Pool::pool_id` of the Solana side of the linked pair,
+// which Sui stores when linking. Receivers check it so a message only applies to its own pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddLiquidityPayload {
+    pub pool_id: [u8; 32],
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub recipient_address: Option<[u8; 32]>,
+}
+
+// REMOVE_LIQUIDITY: `[2][pool_id 32][amount_a u64][amount_b u64][recipient 32]`.
+// The recipient is an address on the chain that releases the funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoveLiquidityPayload {
+    pub pool_id: [u8; 32],
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub recipient_address: [u8; 32],
+}
+
+// LINK_POOL: `[3][pool address 32]`, the address the receiving pool links to, left-padded to
+// 32 bytes: the pool object on Sui, the pool's emitter PDA on Solana
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkPoolPayload {
+    pub pool_address: [u8; 32],
+}
+
+// CREATE_POOL: `[4][source pool address 32]`, sent by a factory to create a linked pool.
+// Followed by the pool parameters `[pool_id 32][token_a_mint 32][token_b_mint 32][fee_percentage u64]`,
+// which both receivers require: Sui only reads `pool_id`, to match later liquidity messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatePoolPayload {
+    pub source_link_address: [u8; 32],
+    pub params: Option<CreatePoolParams>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatePoolParams {
+    pub pool_id: [u8; 32],
+    pub token_a_mint: [u8; 32],
+    pub token_b_mint: [u8; 32],
+    pub fee_percentage: u64, // Basis points
+}
+
+// A decoded cross-chain message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgePayload {
+    AddLiquidity(AddLiquidityPayload),
+    RemoveLiquidity(RemoveLiquidityPayload),
+    LinkPool(LinkPoolPayload),
+    CreatePool(CreatePoolPayload),
+}
+
+impl BridgePayload {
+    pub fn op_code(&self) -> BridgeOperationCode {
+        match self {
+            BridgePayload::AddLiquidity(_) => BridgeOperationCode::AddLiquidity,
+            BridgePayload::RemoveLiquidity(_) => BridgeOperationCode::RemoveLiquidity,
+            BridgePayload::LinkPool(_) => BridgeOperationCode::LinkPool,
+            BridgePayload::CreatePool(_) => BridgeOperationCode::CreatePool,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![self.op_code() as u8];
+        match self {
+            BridgePayload::AddLiquidity(body) => {
+                out.extend_from_slice(&body.pool_id);
+                out.extend_from_slice(&body.amount_a.to_be_bytes());
+                out.extend_from_slice(&body.amount_b.to_be_bytes());
+                if let Some(recipient) = body.recipient_address {
+                    out.extend_from_slice(&recipient);
+                }
+            }
+            BridgePayload::RemoveLiquidity(body) => {
+                out.extend_from_slice(&body.pool_id);
+                out.extend_from_slice(&body.amount_a.to_be_bytes());
+                out.extend_from_slice(&body.amount_b.to_be_bytes());
+                out.extend_from_slice(&body.recipient_address);
+            }
+            BridgePayload::LinkPool(body) => out.extend_from_slice(&body.pool_address),
+            BridgePayload::CreatePool(body) => {
+                out.extend_from_slice(&body.source_link_address);
+                if let Some(params) = body.params {
+                    out.extend_from_slice(&params.pool_id);
+                    out.extend_from_slice(&params.token_a_mint);
+                    out.extend_from_slice(&params.token_b_mint);
+                    out.extend_from_slice(&params.fee_percentage.to_be_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    // Decode a bare (version 1) payload
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (&code, body) = bytes.split_first().ok_or(ErrorCode::InvalidVaaPayload)?;
+        Self::decode_body(code, body)
+    }
+
+    fn decode_body(code: u8, body: &[u8]) -> Result<Self> {
+        let mut reader = PayloadReader { bytes: body };
+
+        let payload = match BridgeOperationCode::try_from(code)? {
+            BridgeOperationCode::AddLiquidity => {
+                let pool_id = reader.read_bytes32()?;
+                let amount_a = reader.read_u64()?;
+                let amount_b = reader.read_u64()?;
+                let recipient_address = if reader.is_empty() { None } else { Some(reader.read_bytes32()?) };
+                BridgePayload::AddLiquidity(AddLiquidityPayload { pool_id, amount_a, amount_b, recipient_address })
+            }
+            BridgeOperationCode::RemoveLiquidity => BridgePayload::RemoveLiquidity(RemoveLiquidityPayload {
+                pool_id: reader.read_bytes32()?,
+                amount_a: reader.read_u64()?,
+                amount_b: reader.read_u64()?,
+                recipient_address: reader.read_bytes32()?,
+            }),
+            BridgeOperationCode::LinkPool => BridgePayload::LinkPool(LinkPoolPayload {
+                pool_address: reader.read_bytes32()?,
+            }),
+            BridgeOperationCode::CreatePool => {
+                let source_link_address = reader.read_bytes32()?;
+                let params = if reader.is_empty() {
+                    None
+                } else {
+                    Some(CreatePoolParams {
+                        pool_id: reader.read_bytes32()?,
+                        token_a_mint: reader.read_bytes32()?,
+                        token_b_mint: reader.read_bytes32()?,
+                        fee_percentage: reader.read_u64()?,
+                    })
+                };
+                BridgePayload::CreatePool(CreatePoolPayload { source_link_address, params })
+            }
+            BridgeOperationCode::CreatePoolMirror => return err!(ErrorCode::UnsupportedPayloadKind),
+        };
+
+        // Bodies are fixed-size, trailing bytes mean the sender used a different layout
+        require!(reader.is_empty(), ErrorCode::InvalidVaaPayload);
+        Ok(payload)
+    }
+}
+
+// Versioned message header. Version 2 layout:
+// `[magic 4][version u8][op_code u8][source_chain u16][source_address 32][target_chain u16][nonce u64]
+//  [body_len u16][body]` where `body` is the payload without its op code. A version 1 payload
+// has no header, so its header fields decode as zero and are taken from the VAA instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadEnvelope {
+    pub version: u8,
+    pub source_chain: u16,        // Wormhole chain ID of the sender
+    pub source_address: [u8; 32], // Pool that sent the message, left-padded to 32 bytes
+    pub target_chain: u16,        // Wormhole chain ID the message is meant for
+    pub nonce: u64,               // Sender-chosen, unique per emitter
+    pub payload: BridgePayload,
+}
+
+impl PayloadEnvelope {
+    pub const HEADER_SIZE: usize = 4 + 1 + 1 + 2 + 32 + 2 + 8 + 2;
+
+    // Envelope for a message published from Solana
+    pub fn outbound(payload: BridgePayload, source_address: [u8; 32], target_chain: u16, nonce: u64) -> Self {
+        PayloadEnvelope {
+            version: OUTBOUND_PROTOCOL_VERSION,
+            source_chain: wormhole_anchor_sdk::wormhole::CHAIN_ID_SOLANA,
+            source_address,
+            target_chain,
+            nonce,
+            payload,
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        match self.version {
+            PROTOCOL_VERSION_1 => Ok(self.payload.encode()),
+            PROTOCOL_VERSION_2 => {
+                let bare = self.payload.encode();
+                let body = &bare[1..];
+                let body_len = u16::try_from(body.len()).map_err(|_| ErrorCode::InvalidVaaPayload)?;
+
+                let mut out = Vec::with_capacity(Self::HEADER_SIZE + body.len());
+                out.extend_from_slice(&PAYLOAD_MAGIC);
+                out.push(self.version);
+                out.push(bare[0]);
+                out.extend_from_slice(&self.source_chain.to_be_bytes());
+                out.extend_from_slice(&self.source_address);
+                out.extend_from_slice(&self.target_chain.to_be_bytes());
+                out.extend_from_slice(&self.nonce.to_be_bytes());
+                out.extend_from_slice(&body_len.to_be_bytes());
+                out.extend_from_slice(body);
+                Ok(out)
+            }
+            _ => err!(ErrorCode::UnsupportedPayloadVersion),
+        }
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(&PAYLOAD_MAGIC) {
+            return Ok(PayloadEnvelope {
+                version: PROTOCOL_VERSION_1,
+                source_chain: 0,
+                source_address: [0u8; 32],
+                target_chain: 0,
+                nonce: 0,
+                payload: BridgePayload::decode(bytes)?,
+            });
+        }
+
+        let mut reader = PayloadReader { bytes: &bytes[PAYLOAD_MAGIC.len()..] };
+        let version = reader.read_u8()?;
+        match version {
+            PROTOCOL_VERSION_2 => {
+                let code = reader.read_u8()?;
+                let source_chain = reader.read_u16()?;
+                let source_address = reader.read_bytes32()?;
+                let target_chain = reader.read_u16()?;
+                let nonce = reader.read_u64()?;
+                let body_len = reader.read_u16()? as usize;
+                // The length prefix must frame exactly the rest of the payload
+                require!(reader.bytes.len() == body_len, ErrorCode::InvalidVaaPayload);
+
+                Ok(PayloadEnvelope {
+                    version,
+                    source_chain,
+                    source_address,
+                    target_chain,
+                    nonce,
+                    payload: BridgePayload::decode_body(code, reader.bytes)?,
+                })
+            }
+            // Version 1 never had a header
+            _ => err!(ErrorCode::UnsupportedPayloadVersion),
+        }
+    }
 }
 
-// Payload structure for completing an AddLiquidity operation initiated on Sui
-#[derive(BorshDeserialize, BorshSerialize, Debug)]
-pub struct AddLiquidityCompletionPayload {
-    pub recipient_address: [u8; 32], // Solana address (as bytes) to receive LP tokens
-    pub lp_amount_to_mint: u64,      // Amount of LP tokens calculated on Sui side
-    pub original_pool_id: [u8; 32],  // Pool ID from Sui to verify against Solana pool
+// Cursor over a fixed-layout body
+struct PayloadReader<'a> {
+    bytes: &'a [u8],
 }
 
-// Payload structure for completing a RemoveLiquidity operation initiated on Sui
-#[derive(BorshDeserialize, BorshSerialize, Debug)]
-pub struct RemoveLiquidityCompletionPayload {
-    pub recipient_address: [u8; 32], // Solana address (as bytes) to receive tokens
-    pub amount_a_to_transfer: u64,   // Amount of token A calculated on Sui side
-    pub amount_b_to_transfer: u64,   // Amount of token B calculated on Sui side
-    pub original_pool_id: [u8; 32],  // Pool ID from Sui to verify against Solana pool
+impl<'a> PayloadReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        require!(self.bytes.len() >= N, ErrorCode::InvalidVaaPayload);
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take::<2>()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take::<8>()?))
+    }
+
+    fn read_bytes32(&mut self) -> Result<[u8; 32]> {
+        self.take::<32>()
+    }
 }
 
-// Generic payload wrapper (optional, but can be useful)
-// The first byte indicates the operation type, followed by the specific payload data
-// Deserialization needs to handle this structure.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT: [u8; 32] = [0xab; 32];
+    const POOL_ID: [u8; 32] = [0x9d; 32];
+
+    // Payload as built by `liquidity_pool::request_remove_liquidity` on Sui:
+    // OPERATION_REMOVE_LIQUIDITY, linked_pool_id, u64_to_bytes(amount_a), u64_to_bytes(amount_b), recipient
+    fn sui_remove_liquidity(amount_a: u64, amount_b: u64) -> Vec<u8> {
+        let mut bytes = vec![2u8];
+        bytes.extend_from_slice(&POOL_ID);
+        bytes.extend_from_slice(&amount_a.to_be_bytes());
+        bytes.extend_from_slice(&amount_b.to_be_bytes());
+        bytes.extend_from_slice(&RECIPIENT);
+        bytes
+    }
+
+    #[test]
+    fn op_codes_match_sui_types() {
+        assert_eq!(BridgeOperationCode::CreatePoolMirror as u8, 0);
+        assert_eq!(BridgeOperationCode::AddLiquidity as u8, 1);
+        assert_eq!(BridgeOperationCode::RemoveLiquidity as u8, 2);
+        assert_eq!(BridgeOperationCode::LinkPool as u8, 3);
+        assert_eq!(BridgeOperationCode::CreatePool as u8, 4);
+        for code in 0u8..=4 {
+            assert_eq!(BridgeOperationCode::try_from(code).unwrap() as u8, code);
+        }
+        assert!(BridgeOperationCode::try_from(5).is_err());
+    }
+
+    #[test]
+    fn add_liquidity_golden() {
+        let payload = BridgePayload::AddLiquidity(AddLiquidityPayload {
+            pool_id: POOL_ID,
+            amount_a: 1_000_000,
+            amount_b: 0x0102_0304_0506_0708,
+            recipient_address: None,
+        });
+        let mut expected = vec![0x01];
+        expected.extend_from_slice(&POOL_ID);
+        expected.extend_from_slice(&[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x42, 0x40, //
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ]);
+        // Sui's process_vaa_for_pool asserts this exact length
+        assert_eq!(expected.len(), 49);
+        assert_eq!(payload.encode(), expected);
+        assert_eq!(BridgePayload::decode(&expected).unwrap(), payload);
+    }
+
+    #[test]
+    fn add_liquidity_with_recipient_round_trips() {
+        let payload = BridgePayload::AddLiquidity(AddLiquidityPayload {
+            pool_id: POOL_ID,
+            amount_a: 5,
+            amount_b: 7,
+            recipient_address: Some(RECIPIENT),
+        });
+        let bytes = payload.encode();
+        assert_eq!(bytes.len(), 81);
+        assert_eq!(&bytes[49..], &RECIPIENT);
+        assert_eq!(BridgePayload::decode(&bytes).unwrap(), payload);
+    }
+
+    #[test]
+    fn remove_liquidity_golden() {
+        let expected = sui_remove_liquidity(250_000, 1);
+        assert_eq!(expected.len(), 81);
+        assert_eq!(expected[0], 0x02);
+        assert_eq!(&expected[1..33], &POOL_ID);
+        assert_eq!(&expected[33..49], &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xd0, 0x90, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        ]);
+
+        let payload = BridgePayload::RemoveLiquidity(RemoveLiquidityPayload {
+            pool_id: POOL_ID,
+            amount_a: 250_000,
+            amount_b: 1,
+            recipient_address: RECIPIENT,
+        });
+        assert_eq!(BridgePayload::decode(&expected).unwrap(), payload);
+        assert_eq!(payload.encode(), expected);
+    }
+
+    #[test]
+    fn link_and_create_pool_golden() {
+        let mut link = vec![0x03];
+        link.extend_from_slice(&RECIPIENT);
+        assert_eq!(
+            BridgePayload::decode(&link).unwrap(),
+            BridgePayload::LinkPool(LinkPoolPayload { pool_address: RECIPIENT })
+        );
+
+        let mut create = vec![0x04];
+        create.extend_from_slice(&RECIPIENT);
+        let payload = BridgePayload::CreatePool(CreatePoolPayload { source_link_address: RECIPIENT, params: None });
+        assert_eq!(BridgePayload::decode(&create).unwrap(), payload);
+        assert_eq!(payload.encode(), create);
+    }
+
+    #[test]
+    fn create_pool_with_params_golden() {
+        let payload = BridgePayload::CreatePool(CreatePoolPayload {
+            source_link_address: RECIPIENT,
+            params: Some(CreatePoolParams {
+                pool_id: [0x11; 32],
+                token_a_mint: [0x22; 32],
+                token_b_mint: [0x33; 32],
+                fee_percentage: 30,
+            }),
+        });
+        let bytes = payload.encode();
+
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(&RECIPIENT);
+        expected.extend_from_slice(&[0x11; 32]);
+        expected.extend_from_slice(&[0x22; 32]);
+        expected.extend_from_slice(&[0x33; 32]);
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0x1e]);
+        assert_eq!(bytes, expected);
+        assert_eq!(BridgePayload::decode(&bytes).unwrap(), payload);
+
+        // Parameters are all or nothing
+        assert!(BridgePayload::decode(&bytes[..bytes.len() - 8]).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        // Empty, unknown op code, reserved op code
+        assert!(BridgePayload::decode(&[]).is_err());
+        assert!(BridgePayload::decode(&[9]).is_err());
+        assert!(BridgePayload::decode(&[0]).is_err());
+
+        // Truncated and over-long bodies
+        let bytes = sui_remove_liquidity(1, 2);
+        assert!(BridgePayload::decode(&bytes[..80]).is_err());
+        let mut long = bytes.clone();
+        long.push(0);
+        assert!(BridgePayload::decode(&long).is_err());
+
+        // ADD_LIQUIDITY is either 49 or 81 bytes, and the pool ID is not optional
+        let add = BridgePayload::AddLiquidity(AddLiquidityPayload {
+            pool_id: POOL_ID,
+            amount_a: 1,
+            amount_b: 2,
+            recipient_address: Some(RECIPIENT),
+        })
+        .encode();
+        assert!(BridgePayload::decode(&add[..60]).is_err());
+        assert!(BridgePayload::decode(&add[..17]).is_err());
+    }
+
+    fn enveloped_remove_liquidity() -> PayloadEnvelope {
+        PayloadEnvelope {
+            version: PROTOCOL_VERSION_2,
+            source_chain: CHAIN_ID_SUI,
+            source_address: [0x5c; 32],
+            target_chain: 1,
+            nonce: 0x0a0b,
+            payload: BridgePayload::RemoveLiquidity(RemoveLiquidityPayload {
+                pool_id: POOL_ID,
+                amount_a: 250_000,
+                amount_b: 1,
+                recipient_address: RECIPIENT,
+            }),
+        }
+    }
+
+    #[test]
+    fn envelope_v2_golden() {
+        let envelope = enveloped_remove_liquidity();
+        let bytes = envelope.encode().unwrap();
+
+        let mut expected = vec![
+            b'O', b'M', b'N', b'I', // magic
+            0x02,                   // version
+            0x02,                   // REMOVE_LIQUIDITY
+            0x00, 0x15,             // source chain (Sui)
+        ];
+        expected.extend_from_slice(&[0x5c; 32]); // source pool
+        expected.extend_from_slice(&[
+            0x00, 0x01,             // target chain (Solana)
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x0b, // nonce
+            0x00, 0x50,             // body length
+        ]);
+        expected.extend_from_slice(&sui_remove_liquidity(250_000, 1)[1..]);
+        assert_eq!(bytes.len(), PayloadEnvelope::HEADER_SIZE + 80);
+        assert_eq!(bytes, expected);
+        assert_eq!(PayloadEnvelope::decode(&bytes).unwrap(), envelope);
+    }
+
+    #[test]
+    fn envelope_decodes_version_1_bodies() {
+        let envelope = PayloadEnvelope::decode(&sui_remove_liquidity(250_000, 1)).unwrap();
+        assert_eq!(envelope.version, PROTOCOL_VERSION_1);
+        assert_eq!((envelope.source_chain, envelope.target_chain, envelope.nonce), (0, 0, 0));
+        assert_eq!(envelope.source_address, [0u8; 32]);
+        assert_eq!(envelope.payload, enveloped_remove_liquidity().payload);
+
+        // Outbound messages stay on the bare layout Sui understands
+        let outbound = PayloadEnvelope::outbound(envelope.payload, [0x5c; 32], CHAIN_ID_SUI, 7);
+        assert_eq!(outbound.encode().unwrap(), sui_remove_liquidity(250_000, 1));
+    }
+
+    #[test]
+    fn envelope_rejects_unknown_versions() {
+        let mut bytes = enveloped_remove_liquidity().encode().unwrap();
+        for version in [0u8, 1, 3, 0xff] {
+            bytes[4] = version;
+            let err = PayloadEnvelope::decode(&bytes).unwrap_err();
+            assert_eq!(err, ErrorCode::UnsupportedPayloadVersion.into());
+        }
+
+        let mut envelope = enveloped_remove_liquidity();
+        envelope.version = 3;
+        assert!(envelope.encode().is_err());
+    }
+
+    #[test]
+    fn envelope_rejects_bad_framing() {
+        let bytes = enveloped_remove_liquidity().encode().unwrap();
+
+        // Truncated header, truncated body, trailing bytes
+        assert!(PayloadEnvelope::decode(&bytes[..10]).is_err());
+        assert!(PayloadEnvelope::decode(&bytes[..bytes.len() - 1]).is_err());
+        let mut long = bytes.clone();
+        long.push(0);
+        assert!(PayloadEnvelope::decode(&long).is_err());
+
+        // Length prefix disagreeing with the op code's fixed layout
+        let mut short_body = bytes[..bytes.len() - 32].to_vec();
+        short_body[PayloadEnvelope::HEADER_SIZE - 1] = 48;
+        assert!(PayloadEnvelope::decode(&short_body).is_err());
+    }
+}
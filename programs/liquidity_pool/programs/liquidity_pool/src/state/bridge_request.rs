@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
 
+// Record of a processed Wormhole message (PDA per emitter chain, emitter address and sequence),
+// its existence with a non-pending status is what prevents a VAA from being redeemed twice
 #[account]
 #[derive(Default)]
 pub struct BridgeRequest {
@@ -9,28 +12,57 @@ pub struct BridgeRequest {
     pub status: BridgeStatus,       // Bridge request status
     pub payload: Vec<u8>,           // Bridge operation payload (variable size)
     pub created_at: i64,            // Creation timestamp
-    // Add bump seed if this account is a PDA
-    // pub bump: u8,
+    pub bump: u8,                   // PDA bump seed
+    pub pool: Pubkey,               // Pool the message was processed against
+    pub release_at: i64,            // Delayed requests: earliest time they can be released
+    pub failure_reason: BridgeFailure, // Failed requests: why the operation couldn't execute
+    pub surplus_a: u64,             // Completed deposits: amounts over the pool ratio, owed back to the
+    pub surplus_b: u64,             // source chain (see `refund_bridge_request`)
+    pub linked_address: [u8; 32],   // Pool link the message was processed under, follow-ups require it
+}
+
+impl BridgeRequest {
+    pub const SEED: &'static [u8] = b"bridge_request";
+    pub const MAX_PAYLOAD_SIZE: usize = 1024;
+
+    // Discriminator (8) + u64 (1) + u16 (1) + [u8; 32] (1) + Enum (1) + Vec (4 + payload) + i64 (1) + u8 (1)
+    // + Pubkey (1) + i64 (1) + Enum (1) + u64 (2) + [u8; 32] (1)
+    pub fn size(payload_len: usize) -> usize {
+        8 + 8 + 2 + 32 + 1 + (4 + payload_len) + 8 + 1 + 32 + 8 + 1 + 8 + 8 + 32
+    }
 }
 
 // Define BridgeStatus enum
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum BridgeStatus {
+    #[default]
     Pending,
     Completed,
     Failed,
+    Delayed,   // Over a rate limit, waiting for `release_bridge_request`
+    Cancelled, // Delayed request dropped by the admin
+    Refunded,  // Failed request compensated on the source chain
 }
 
-impl Default for BridgeStatus {
-    fn default() -> Self {
-        BridgeStatus::Pending
-    }
+// Reason a bridge operation was recorded as Failed instead of reverting.
+// These are conditions that can clear up, so the request can be retried (or refunded).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BridgeFailure {
+    #[default]
+    None,
+    PoolNotActive,        // Deposit into a paused or deprecated pool
+    ZeroLiquidity,        // Deposit too small to mint any LP tokens
+    InsufficientReserves, // Withdrawal larger than the local LP reserves
 }
 
-// Note: Size calculation for accounts with Vec<u8> is tricky.
-// Anchor usually handles this, but for manual calculation:
-// Discriminator (8) + u64 (1) + u16 (1) + [u8; 32] (1) + Enum (1 byte for status) + Vec (4 bytes for length) + i64 (1) + Payload size
-// The maximum size depends on the maximum payload length allowed.
-// Let's assume a max payload of 1024 bytes for estimation as per README.
-// SIZE ~ 8 + 8 + 2 + 32 + 1 + 4 + 8 + 1024 = 1087 bytes (approx max)
-// It's often better to let Anchor manage size or use fixed-size arrays if possible.
+// The error a retry that fails again reverts with
+impl From<BridgeFailure> for Error {
+    fn from(reason: BridgeFailure) -> Self {
+        match reason {
+            BridgeFailure::None => ErrorCode::BridgeRequestNotFailed.into(),
+            BridgeFailure::PoolNotActive => ErrorCode::PoolPaused.into(),
+            BridgeFailure::ZeroLiquidity => ErrorCode::ZeroLiquidityMinted.into(),
+            BridgeFailure::InsufficientReserves => ErrorCode::InsufficientPoolReserves.into(),
+        }
+    }
+}
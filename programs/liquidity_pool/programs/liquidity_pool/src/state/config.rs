@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::Pool;
+
+// Global program configuration (singleton PDA seeded by b"config")
+#[account]
+#[derive(Default)]
+pub struct Config {
+    pub admin: Pubkey,                     // Authority allowed to manage config and pools
+    pub fee_tiers: Vec<u64>,               // Allowed pool fee percentages (basis points)
+    pub default_protocol_fee_share: u64,   // Protocol fee share assigned to new pools (basis points of the fee)
+    pub paused: bool,                      // Global pause flag, blocks all user-facing instructions
+    pub bump: u8,                          // PDA bump seed for the config account
+}
+
+impl Config {
+    pub const SEED: &'static [u8] = b"config";
+    pub const MAX_FEE_TIERS: usize = 8;
+
+    // Discriminator (8) + Pubkey (1) + Vec<u64> (4 + 8 * MAX_FEE_TIERS) + u64 (1) + bool (1) + u8 (1)
+    pub const SIZE: usize = 8 + 32 + (4 + 8 * Self::MAX_FEE_TIERS) + 8 + 1 + 1;
+
+    pub fn is_fee_tier_allowed(&self, fee_percentage: u64) -> bool {
+        self.fee_tiers.contains(&fee_percentage)
+    }
+
+    // New pools can only use an admin-approved tier. Each tier of a pair is its own pool (the fee
+    // is part of the pool seeds). Tiers are bounded when configured; the bound is checked again
+    // here so an out-of-range fee reports as such.
+    pub fn require_fee_tier(&self, fee_percentage: u64) -> Result<()> {
+        require!(fee_percentage < Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+        require!(self.is_fee_tier_allowed(fee_percentage), ErrorCode::FeeTierNotAllowed);
+        Ok(())
+    }
+
+    // Fee tiers must be non-empty, bounded, unique and below 100% (a 100% fee leaves nothing to swap)
+    pub fn validate_fee_tiers(fee_tiers: &[u64]) -> Result<()> {
+        require!(
+            !fee_tiers.is_empty() && fee_tiers.len() <= Self::MAX_FEE_TIERS,
+            ErrorCode::InvalidFeeTiers
+        );
+        for (i, tier) in fee_tiers.iter().enumerate() {
+            require!(*tier < Pool::FEE_DENOMINATOR, ErrorCode::InvalidFeePercentage);
+            require!(!fee_tiers[..i].contains(tier), ErrorCode::InvalidFeeTiers);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(fee_tiers: &[u64]) -> Config {
+        Config { fee_tiers: fee_tiers.to_vec(), ..Config::default() }
+    }
+
+    #[test]
+    fn fee_tiers_are_bounded_and_unique() {
+        assert!(Config::validate_fee_tiers(&[1, 5, 30, 100]).is_ok());
+        assert!(Config::validate_fee_tiers(&[Pool::FEE_DENOMINATOR - 1]).is_ok());
+        assert!(Config::validate_fee_tiers(&[30, Pool::FEE_DENOMINATOR]).is_err());
+        assert!(Config::validate_fee_tiers(&[5, 30, 5]).is_err());
+        assert!(Config::validate_fee_tiers(&[]).is_err());
+        assert!(Config::validate_fee_tiers(&[1; Config::MAX_FEE_TIERS + 1]).is_err());
+    }
+
+    #[test]
+    fn pools_only_use_configured_tiers() {
+        let config = config(&[1, 5, 30, 100, Pool::FEE_DENOMINATOR]);
+        for tier in [1, 5, 30, 100] {
+            assert!(config.require_fee_tier(tier).is_ok());
+        }
+        assert_eq!(config.require_fee_tier(25).unwrap_err(), ErrorCode::FeeTierNotAllowed.into());
+        assert_eq!(
+            config.require_fee_tier(Pool::FEE_DENOMINATOR).unwrap_err(),
+            ErrorCode::InvalidFeePercentage.into()
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+// Trusted Wormhole emitter for a foreign chain (one PDA per chain ID),
+// mirroring `trusted_factories` in the Sui factory
+#[account]
+#[derive(Default)]
+pub struct ForeignEmitter {
+    pub chain: u16,          // Wormhole chain ID of the foreign chain
+    pub address: [u8; 32],   // Trusted emitter address (Wormhole format)
+    pub bump: u8,            // PDA bump seed
+}
+
+impl ForeignEmitter {
+    pub const SEED: &'static [u8] = b"foreign_emitter";
+
+    // Discriminator (8) + u16 (1) + [u8; 32] (1) + u8 (1)
+    pub const SIZE: usize = 8 + 2 + 32 + 1;
+
+    pub fn verify(&self, emitter_address: &[u8; 32]) -> bool {
+        self.address == *emitter_address
+    }
+}
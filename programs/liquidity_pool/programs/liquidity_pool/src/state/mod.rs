@@ -1,7 +1,15 @@
 pub mod pool;
 pub mod position;
 pub mod bridge_request;
+pub mod config;
+pub mod foreign_emitter;
+pub mod rate_limit;
+pub mod oracle;
 
 pub use pool::*;
 pub use position::*;
 pub use bridge_request::*;
+pub use config::*;
+pub use foreign_emitter::*;
+pub use rate_limit::*;
+pub use oracle::*;
@@ -0,0 +1,251 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::math::mul_div_u128;
+
+// Pool price accumulators at one point in time.
+// Prices are the pool's marginal prices in whole tokens (see `Pool::spot_prices`) as UQ64.64
+// fixed point, and the cumulatives are the sum of price * seconds, wrapping on overflow like
+// Uniswap v2. Only differences between two observations are meaningful:
+// twap = (cumulative_1 - cumulative_0) / (t1 - t0).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_a_cumulative: u128, // Price of token A in token B
+    pub price_b_cumulative: u128, // Price of token B in token A
+}
+
+impl Observation {
+    // i64 (1) + u128 (2)
+    pub const SIZE: usize = 8 + 16 * 2;
+
+    // Accumulate the prices of A in B and of B in A up to `now`; an empty pool (None) adds nothing
+    pub fn accumulate(&self, prices: Option<(u128, u128)>, now: i64) -> Observation {
+        if now <= self.timestamp {
+            return *self;
+        }
+        let elapsed = (now - self.timestamp) as u128;
+        let mut next = Observation { timestamp: now, ..*self };
+        if let Some((price_a, price_b)) = prices {
+            next.price_a_cumulative = self.price_a_cumulative.wrapping_add(price_a.wrapping_mul(elapsed));
+            next.price_b_cumulative = self.price_b_cumulative.wrapping_add(price_b.wrapping_mul(elapsed));
+        }
+        next
+    }
+
+    // Linear interpolation towards a later observation; exact when the price was constant in between
+    fn interpolate(&self, later: &Observation, timestamp: i64) -> Observation {
+        let span = (later.timestamp - self.timestamp) as u128;
+        let elapsed = (timestamp - self.timestamp) as u128;
+        let step = |from: u128, to: u128| from.wrapping_add(to.wrapping_sub(from) / span * elapsed);
+        Observation {
+            timestamp,
+            price_a_cumulative: step(self.price_a_cumulative, later.price_a_cumulative),
+            price_b_cumulative: step(self.price_b_cumulative, later.price_b_cumulative),
+        }
+    }
+}
+
+// UQ64.64 ratio numerator / denominator, saturating at the top of the range
+pub fn fixed_point_price(numerator: u128, denominator: u128) -> u128 {
+    mul_div_u128(numerator, 1 << 64, denominator).unwrap_or(u128::MAX)
+}
+
+// Time-weighted average prices over the last `seconds_ago` seconds, UQ64.64
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Twap {
+    pub seconds_ago: u32,
+    pub price_a: u128, // Average price of token A in token B
+    pub price_b: u128, // Average price of token B in token A
+}
+
+// Optional ring buffer of price observations for one pool, PDA [SEED, pool].
+// Observations are appended by every swap of the pool (see `Pool::has_oracle`) and by
+// `record_observation`, at most one per second; once `capacity` is reached the oldest one
+// is overwritten.
+#[account]
+pub struct Oracle {
+    pub pool: Pubkey,                   // Pool the observations belong to
+    pub capacity: u16,                  // Maximum number of observations kept
+    pub index: u16,                     // Position of the newest observation
+    pub bump: u8,                       // PDA bump seed
+    pub observations: Vec<Observation>, // Grows up to `capacity`, then wraps
+}
+
+impl Oracle {
+    pub const SEED: &'static [u8] = b"oracle";
+
+    // Keeps the account within the 10 KiB a program can allocate at creation
+    pub const MAX_CAPACITY: u16 = 250;
+
+    // Upper bound on `seconds_ago` entries, so the result fits in the 1 KiB of return data
+    pub const MAX_OBSERVE: usize = 24;
+
+    // Discriminator (8) + Pubkey (1) + u16 (2) + u8 (1) + Vec prefix (4) + observations
+    pub fn size(capacity: u16) -> usize {
+        8 + 32 + 2 * 2 + 1 + 4 + capacity as usize * Observation::SIZE
+    }
+
+    // Append an observation, overwriting the oldest one when full
+    pub fn write(&mut self, observation: Observation) {
+        if let Some(newest) = self.observations.get(self.index as usize) {
+            if observation.timestamp <= newest.timestamp {
+                return;
+            }
+        }
+        if self.observations.len() < self.capacity as usize {
+            self.observations.push(observation);
+            self.index = (self.observations.len() - 1) as u16;
+        } else {
+            self.index = (self.index + 1) % self.capacity;
+            self.observations[self.index as usize] = observation;
+        }
+    }
+
+    // Observations from oldest to newest
+    pub fn ordered(&self) -> Vec<Observation> {
+        let split = if self.observations.len() < self.capacity as usize {
+            0
+        } else {
+            (self.index as usize + 1) % self.observations.len().max(1)
+        };
+        let (newer, older) = self.observations.split_at(split);
+        older.iter().chain(newer).copied().collect()
+    }
+
+    // TWAPs ending at `current.timestamp`. `recent` are observations newer than the buffer
+    // (the pool's own accumulators and `current`), in time order.
+    pub fn observe(&self, recent: &[Observation], seconds_ago: &[u32]) -> Result<Vec<Twap>> {
+        require!(
+            !seconds_ago.is_empty() && seconds_ago.len() <= Self::MAX_OBSERVE,
+            ErrorCode::InvalidObservationWindow
+        );
+        let mut history = self.ordered();
+        for observation in recent {
+            let newer = match history.last() {
+                Some(last) => observation.timestamp > last.timestamp,
+                None => true,
+            };
+            if newer {
+                history.push(*observation);
+            }
+        }
+        let current = *history.last().ok_or(ErrorCode::ObservationTooOld)?;
+
+        seconds_ago
+            .iter()
+            .map(|&window| {
+                require!(window > 0, ErrorCode::InvalidObservationWindow);
+                let past = cumulative_at(&history, current.timestamp - window as i64)?;
+                Ok(Twap {
+                    seconds_ago: window,
+                    price_a: current.price_a_cumulative.wrapping_sub(past.price_a_cumulative) / window as u128,
+                    price_b: current.price_b_cumulative.wrapping_sub(past.price_b_cumulative) / window as u128,
+                })
+            })
+            .collect()
+    }
+}
+
+// Accumulators at `timestamp`, interpolated between the surrounding observations
+fn cumulative_at(history: &[Observation], timestamp: i64) -> Result<Observation> {
+    // First observation after `timestamp`; everything before it is at or before `timestamp`
+    let next = history.partition_point(|observation| observation.timestamp <= timestamp);
+    require!(next > 0, ErrorCode::ObservationTooOld);
+    let before = history[next - 1];
+    match history.get(next) {
+        Some(after) if before.timestamp < timestamp => Ok(before.interpolate(after, timestamp)),
+        _ => Ok(before),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u128 = 1 << 64;
+
+    fn oracle(capacity: u16) -> Oracle {
+        Oracle { pool: Pubkey::default(), capacity, index: 0, bump: 0, observations: Vec::new() }
+    }
+
+    fn at(timestamp: i64) -> Observation {
+        Observation { timestamp, ..Observation::default() }
+    }
+
+    // Constant-product prices of reserves with equal decimals
+    fn prices(reserve_a: u128, reserve_b: u128) -> Option<(u128, u128)> {
+        Some((fixed_point_price(reserve_b, reserve_a), fixed_point_price(reserve_a, reserve_b)))
+    }
+
+    #[test]
+    fn accumulates_price_times_elapsed_seconds() {
+        let start = at(100);
+        let next = start.accumulate(prices(1_000, 2_000), 110);
+        assert_eq!(next.price_a_cumulative, 2 * ONE * 10);
+        assert_eq!(next.price_b_cumulative, ONE / 2 * 10);
+        // Same second and empty pools add nothing
+        assert_eq!(next.accumulate(prices(1, 1), 110), next);
+        assert_eq!(next.accumulate(None, 120).price_a_cumulative, next.price_a_cumulative);
+        assert_eq!(next.accumulate(None, 120).timestamp, 120);
+    }
+
+    #[test]
+    fn accumulators_wrap_instead_of_overflowing() {
+        let start = Observation { timestamp: 0, price_a_cumulative: u128::MAX - ONE + 1, price_b_cumulative: 0 };
+        let next = start.accumulate(prices(1, 1), 3);
+        assert_eq!(next.price_a_cumulative, 2 * ONE);
+
+        let mut buffer = oracle(4);
+        buffer.write(start);
+        let twap = buffer.observe(&[next], &[3]).unwrap();
+        assert_eq!(twap[0].price_a, ONE);
+    }
+
+    #[test]
+    fn ring_buffer_keeps_the_newest_observations() {
+        let mut buffer = oracle(3);
+        for timestamp in 1..=5 {
+            buffer.write(at(timestamp));
+        }
+        // Not more than one observation per second
+        buffer.write(at(5));
+        let timestamps: Vec<i64> = buffer.ordered().iter().map(|o| o.timestamp).collect();
+        assert_eq!(timestamps, vec![3, 4, 5]);
+        assert_eq!(buffer.observations.len(), 3);
+    }
+
+    #[test]
+    fn twap_weights_prices_by_time() {
+        let mut buffer = oracle(8);
+        // Price 1 for 30 seconds, then 4 for 10 seconds
+        let start = at(1_000);
+        let changed = start.accumulate(prices(1_000, 1_000), 1_030);
+        let now = changed.accumulate(prices(1_000, 4_000), 1_040);
+        buffer.write(start);
+        buffer.write(changed);
+
+        let twaps = buffer.observe(&[now], &[10, 20, 40]).unwrap();
+        assert_eq!(twaps[0].price_a, 4 * ONE);
+        // Interpolated at 1_020: 10s at 1 and 10s at 4
+        assert_eq!(twaps[1].price_a, 5 * ONE / 2);
+        assert_eq!(twaps[2].price_a, (30 * ONE + 40 * ONE) / 40);
+        assert_eq!(twaps[0].price_b, ONE / 4);
+    }
+
+    #[test]
+    fn rejects_windows_older_than_the_history() {
+        let mut buffer = oracle(2);
+        buffer.write(at(1_000));
+        let now = at(1_000).accumulate(prices(5, 5), 1_100);
+        assert!(buffer.observe(&[now], &[100]).is_ok());
+        assert_eq!(
+            buffer.observe(&[now], &[101]).unwrap_err(),
+            ErrorCode::ObservationTooOld.into()
+        );
+        assert_eq!(
+            buffer.observe(&[now], &[0]).unwrap_err(),
+            ErrorCode::InvalidObservationWindow.into()
+        );
+        assert!(buffer.observe(&[now], &[1; Oracle::MAX_OBSERVE + 1]).is_err());
+    }
+}
@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use crate::curve::{decimal_rates, ramped_amplification, stable_spot_price, Curve, StableSwap};
+use crate::errors::ErrorCode;
+use crate::state::{fixed_point_price, Observation};
 
 #[account]
 #[derive(Default)]
@@ -11,36 +15,552 @@ pub struct Pool {
     pub lp_mint: Pubkey,            // LP token mint (Mint PDA owned by authority)
     pub fee_percentage: u64,        // Fee percentage (basis points, e.g., 30 for 0.3%)
     pub total_liquidity: u64,       // Total LP token amount currently minted
-    pub pool_id: [u8; 32],          // Unique pool identifier matching with Sui side
-    pub status: u8,                 // Pool status (0: active, 1: paused)
+    pub pool_id: [u8; 32],          // Unique pool identifier matching with Sui side (see `derive_pool_id`)
+    pub status: PoolStatus,         // Pool status (Active, Paused or Deprecated)
     pub last_updated_at: i64,       // Last update timestamp (Unix timestamp)
     pub protocol_fee_a: u64,        // Accumulated protocol fees in token A
     pub protocol_fee_b: u64,        // Accumulated protocol fees in token B
+    pub protocol_fee_share: u64,    // Share of each swap fee taken by the protocol (basis points of the fee)
+    pub fee_recipient: Pubkey,      // Owner of the token accounts that receive collected protocol fees
     pub bump: u8,                   // PDA bump seed for the authority
     pub lp_mint_bump: u8,           // PDA bump seed for the LP mint
     pub token_a_bump: u8,           // PDA bump seed for token A account
     pub token_b_bump: u8,           // PDA bump seed for token B account
+    pub linked_chain_id: u16,       // Wormhole chain ID of the linked remote pool (0 = not linked)
+    pub linked_address: [u8; 32],   // Address of the linked remote pool, left-padded to 32 bytes
+    pub remote_reserve_a: u64,      // Token A held by the linked pool on behalf of this pool's LPs
+    pub remote_reserve_b: u64,      // Token B held by the linked pool on behalf of this pool's LPs
+    pub price_a_cumulative: u128,   // Sum of token A's UQ64.64 price in token B times seconds (wrapping)
+    pub price_b_cumulative: u128,   // Sum of token B's UQ64.64 price in token A times seconds (wrapping)
+    pub price_updated_at: i64,      // Timestamp the price accumulators were last brought up to date
+    pub reserve_a: u64,             // Token A in the vault that belongs to LPs (excludes fees and donations)
+    pub reserve_b: u64,             // Token B in the vault that belongs to LPs (excludes fees and donations)
+    pub locked_lp_bump: u8,         // PDA bump seed for the locked LP token account
+    pub curve_type: CurveType,      // Pricing curve, fixed at creation
+    pub amp_initial: u64,           // StableSwap amplification at `amp_ramp_start` (0 on constant-product pools)
+    pub amp_target: u64,            // StableSwap amplification from `amp_ramp_end` on
+    pub amp_ramp_start: i64,        // Start of the current amplification ramp (Unix timestamp)
+    pub amp_ramp_end: i64,          // End of the current amplification ramp (Unix timestamp)
+    pub token_a_decimals: u8,       // Token A mint decimals, used to normalise StableSwap balances
+    pub token_b_decimals: u8,       // Token B mint decimals
+    pub has_oracle: bool,           // Set once the pool's observation ring buffer exists; swaps must then pass it
 }
 
 impl Pool {
     // Calculate size based on fields
-    // Discriminator (8) + Pubkey (32 * 6) + u64 (5) + [u8; 32] (1) + u8 (1) + i64 (1) + u8 (4)
-    pub const SIZE: usize = 8 + (32 * 6) + (8 * 5) + 32 + 1 + 8 + 4;
+    // Discriminator (8) + Pubkey (32 * 7) + u64 (5) + [u8; 32] (1) + PoolStatus (1) + i64 (1) + u8 (4)
+    // + linked_chain_id (2) + linked_address (32) + remote reserves (8 * 2)
+    // + price accumulators (16 * 2) + price_updated_at (8) + local reserves (8 * 2) + locked_lp_bump (1)
+    // + curve_type (1) + amplification ramp (8 * 4) + token decimals (2) + has_oracle (1)
+    pub const SIZE: usize = 8 + (32 * 7) + (8 * 5) + 32 + 1 + 8 + 4 + 2 + 32 + (8 * 2) + (16 * 2) + 8 + (8 * 2) + 1
+        + 1 + (8 * 4) + 2 + 1;
+
+    // Denominator for `fee_percentage` (basis points)
+    pub const FEE_DENOMINATOR: u64 = 10_000;
+
+    // LP tokens locked forever by the first deposit, so the LP supply never returns to zero
+    // and the price of one LP token can't be inflated cheaply (Uniswap v2's MINIMUM_LIQUIDITY)
+    pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+    // Pool ID of a pair and fee tier: keccak256("pool" || token_a_mint || token_b_mint || fee_percentage),
+    // the mints as their 32 bytes in canonical order and the fee as a big-endian u64 like on the wire.
+    // Sui computes the same ID from the same bytes (`types::derive_pool_id`).
+    pub fn derive_pool_id(token_a_mint: &Pubkey, token_b_mint: &Pubkey, fee_percentage: u64) -> [u8; 32] {
+        keccak::hashv(&[
+            b"pool",
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            &fee_percentage.to_be_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    // Reserves that belong to liquidity providers. They are tracked rather than read from the
+    // vaults, so tokens sent to a vault directly (donations) never change the LP price.
+    pub fn lp_reserves(&self) -> (u64, u64) {
+        (self.reserve_a, self.reserve_b)
+    }
+
+    // Tokens added to the vaults on behalf of LPs
+    pub fn deposit_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        self.reserve_a = self.reserve_a.checked_add(amount_a).ok_or(ErrorCode::Overflow)?;
+        self.reserve_b = self.reserve_b.checked_add(amount_b).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // Tokens paid out of the vaults; never more than the LPs own locally
+    pub fn withdraw_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        require!(
+            amount_a <= self.reserve_a && amount_b <= self.reserve_b,
+            ErrorCode::InsufficientPoolReserves
+        );
+        self.reserve_a -= amount_a;
+        self.reserve_b -= amount_b;
+        Ok(())
+    }
+
+    // Reserves backing the LP supply: the local LP reserves plus the liquidity held by the
+    // linked remote pool. LP tokens are minted and burned against these, wherever the tokens sit.
+    pub fn combined_reserves(&self) -> Result<(u64, u64)> {
+        let (local_a, local_b) = self.lp_reserves();
+        Ok((
+            local_a.checked_add(self.remote_reserve_a).ok_or(ErrorCode::Overflow)?,
+            local_b.checked_add(self.remote_reserve_b).ok_or(ErrorCode::Overflow)?,
+        ))
+    }
+
+    // Liquidity deposited into (or rebalanced to) the linked remote pool
+    pub fn credit_remote_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        self.remote_reserve_a = self.remote_reserve_a.checked_add(amount_a).ok_or(ErrorCode::Overflow)?;
+        self.remote_reserve_b = self.remote_reserve_b.checked_add(amount_b).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // Liquidity released by the linked remote pool; it can't release more than it holds for us
+    pub fn debit_remote_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        require!(
+            amount_a <= self.remote_reserve_a && amount_b <= self.remote_reserve_b,
+            ErrorCode::InsufficientPoolReserves
+        );
+        self.remote_reserve_a -= amount_a;
+        self.remote_reserve_b -= amount_b;
+        Ok(())
+    }
+
+    // Price accumulators as of the last update
+    pub fn price_observation(&self) -> Observation {
+        Observation {
+            timestamp: self.price_updated_at,
+            price_a_cumulative: self.price_a_cumulative,
+            price_b_cumulative: self.price_b_cumulative,
+        }
+    }
+
+    // Marginal prices of token A in token B and of B in A at the local reserves, UQ64.64 in whole
+    // tokens (both sides scaled to the larger decimals), so a balanced stable pair reads 1 whatever
+    // its mints' decimals. Constant-product pools price at the reserve ratio, StableSwap pools at
+    // the slope of their invariant. None while either side is empty.
+    pub fn spot_prices(&self, now: i64) -> Result<Option<(u128, u128)>> {
+        if self.reserve_a == 0 || self.reserve_b == 0 {
+            return Ok(None);
+        }
+        let (rate_a, rate_b) = decimal_rates(self.token_a_decimals, self.token_b_decimals)?;
+        let x = (self.reserve_a as u128).checked_mul(rate_a).ok_or(ErrorCode::Overflow)?;
+        let y = (self.reserve_b as u128).checked_mul(rate_b).ok_or(ErrorCode::Overflow)?;
+        let prices = match self.curve_type {
+            CurveType::ConstantProduct => (fixed_point_price(y, x), fixed_point_price(x, y)),
+            CurveType::StableSwap => {
+                let amplification = self.amplification(now);
+                (stable_spot_price(amplification, x, y)?, stable_spot_price(amplification, y, x)?)
+            }
+        };
+        Ok(Some(prices))
+    }
+
+    // Bring the price accumulators up to `now` with the reserves the pool has held since the
+    // last update. Call before reserves change, so each price is weighted by how long it held.
+    pub fn accrue_prices(&mut self, now: i64) -> Result<Observation> {
+        let observation = self.price_observation().accumulate(self.spot_prices(now)?, now);
+        self.price_a_cumulative = observation.price_a_cumulative;
+        self.price_b_cumulative = observation.price_b_cumulative;
+        self.price_updated_at = observation.timestamp;
+        Ok(observation)
+    }
+
+    // StableSwap amplification at `now`, following the current ramp
+    pub fn amplification(&self, now: i64) -> u64 {
+        ramped_amplification(self.amp_initial, self.amp_target, self.amp_ramp_start, self.amp_ramp_end, now)
+    }
+
+    // Curve to price with at `now`, oriented for an A to B swap (see `Curve::reversed`)
+    pub fn curve(&self, now: i64) -> Result<Curve> {
+        match self.curve_type {
+            CurveType::ConstantProduct => Ok(Curve::ConstantProduct),
+            CurveType::StableSwap => Ok(Curve::StableSwap(StableSwap::new(
+                self.amplification(now),
+                self.token_a_decimals,
+                self.token_b_decimals,
+            )?)),
+        }
+    }
+
+    // Pool state as reported in events; the LP supply is tracked by `total_liquidity`
+    pub fn snapshot(&self) -> PoolSnapshot {
+        PoolSnapshot {
+            reserve_a: self.reserve_a,
+            reserve_b: self.reserve_b,
+            remote_reserve_a: self.remote_reserve_a,
+            remote_reserve_b: self.remote_reserve_b,
+            lp_supply: self.total_liquidity,
+        }
+    }
+
+    // Whether the pool is linked to a pool on another chain
+    pub fn is_linked(&self) -> bool {
+        self.linked_chain_id != 0
+    }
+
+    // Bridge operations need a linked remote pool, like `EPoolNotLinked` on Sui
+    pub fn require_linked(&self) -> Result<()> {
+        require!(self.is_linked(), ErrorCode::PoolNotLinked);
+        Ok(())
+    }
+
+    // Swaps and deposits are only allowed while the pool is active
+    pub fn require_active(&self) -> Result<()> {
+        match self.status {
+            PoolStatus::Active => Ok(()),
+            PoolStatus::Paused => err!(ErrorCode::PoolPaused),
+            PoolStatus::Deprecated => err!(ErrorCode::InvalidPoolStatus),
+        }
+    }
+}
+
+// Reserves and LP supply of a pool at one point in time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PoolSnapshot {
+    pub reserve_a: u64,        // Local LP reserves
+    pub reserve_b: u64,
+    pub remote_reserve_a: u64, // Held by the linked pool
+    pub remote_reserve_b: u64,
+    pub lp_supply: u64,
 }
 
-// Enum for Pool Status (optional but good practice)
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+// Enum for Pool Status
+// Active: everything allowed. Paused: swaps and deposits blocked, withdrawals allowed.
+// Deprecated: terminal, only withdrawals allowed so LPs can always exit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum PoolStatus {
+    #[default]
     Active,
     Paused,
     Deprecated,
 }
 
-impl Default for PoolStatus {
-    fn default() -> Self {
-        PoolStatus::Active
-    }
+// Enum for the pricing curve, chosen when the pool is created
+// ConstantProduct: x * y = k, for any pair. StableSwap: Curve's invariant with an amplification
+// coefficient, for pegged pairs such as bridged stablecoins or wrapped assets (see `curve`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CurveType {
+    #[default]
+    ConstantProduct,
+    StableSwap,
 }
 
-// Implement conversion from u8 if needed, or use the enum directly in the Pool struct
-// For simplicity, the Pool struct uses u8 for status directly as shown in the README example.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::add_liquidity::{locked_liquidity, quote_deposit};
+    use crate::instructions::remove_liquidity::quote_withdrawal;
+
+    // Pool accounting as the instructions drive it: local vaults, the remote ledger and LP supply.
+    // The vaults hold the LP reserves plus whatever is sent to them directly.
+    struct Model {
+        pool: Pool,
+        vault_a: u64,
+        vault_b: u64,
+        lp_supply: u64,
+    }
+
+    impl Model {
+        fn new() -> Self {
+            Model { pool: Pool::default(), vault_a: 0, vault_b: 0, lp_supply: 0 }
+        }
+
+        fn combined(&self) -> (u64, u64) {
+            self.pool.combined_reserves().unwrap()
+        }
+
+        fn quote(&self, amount_a: u64, amount_b: u64) -> Result<(u64, u64, u64)> {
+            let (reserve_a, reserve_b) = self.combined();
+            quote_deposit(&Curve::ConstantProduct, amount_a, amount_b, 0, 0, reserve_a, reserve_b, self.lp_supply)
+        }
+
+        // add_liquidity / bridge_add_liquidity
+        fn try_deposit_local(&mut self, amount_a: u64, amount_b: u64) -> Result<u64> {
+            let (a, b, minted) = self.quote(amount_a, amount_b)?;
+            self.vault_a += a;
+            self.vault_b += b;
+            self.pool.deposit_reserves(a, b)?;
+            Ok(self.mint(minted))
+        }
+
+        fn deposit_local(&mut self, amount_a: u64, amount_b: u64) -> u64 {
+            self.try_deposit_local(amount_a, amount_b).unwrap()
+        }
+
+        // process_vaa ADD_LIQUIDITY: only the quoted amounts are booked, the surplus stays refundable
+        fn deposit_remote(&mut self, amount_a: u64, amount_b: u64) -> u64 {
+            let (a, b, minted) = self.quote(amount_a, amount_b).unwrap();
+            self.pool.credit_remote_reserves(a, b).unwrap();
+            self.mint(minted)
+        }
+
+        // Tokens sent straight to the vaults, bypassing the program
+        fn donate(&mut self, amount_a: u64, amount_b: u64) {
+            self.vault_a += amount_a;
+            self.vault_b += amount_b;
+        }
+
+        // remove_liquidity
+        fn withdraw_local(&mut self, liquidity: u64) -> Result<(u64, u64)> {
+            let (reserve_a, reserve_b) = self.combined();
+            let (a, b) = quote_withdrawal(liquidity, 0, 0, reserve_a, reserve_b, self.lp_supply)?;
+            self.pool.withdraw_reserves(a, b)?;
+            self.vault_a -= a;
+            self.vault_b -= b;
+            self.burn(liquidity);
+            Ok((a, b))
+        }
+
+        // request_remove_liquidity_remote
+        fn withdraw_remote(&mut self, liquidity: u64) -> Result<()> {
+            let (reserve_a, reserve_b) = self.combined();
+            let (a, b) = quote_withdrawal(liquidity, 0, 0, reserve_a, reserve_b, self.lp_supply)?;
+            self.pool.debit_remote_reserves(a, b)?;
+            self.burn(liquidity);
+            Ok(())
+        }
+
+        // process_vaa REMOVE_LIQUIDITY: Sui LP burned there, paid from our vaults
+        fn pay_remote_withdrawal(&mut self, amount_a: u64, amount_b: u64) {
+            self.pool.withdraw_reserves(amount_a, amount_b).unwrap();
+            self.vault_a -= amount_a;
+            self.vault_b -= amount_b;
+            self.pool.credit_remote_reserves(amount_a, amount_b).unwrap();
+        }
+
+        // Mints `minted` to the depositor, plus the locked liquidity on the first deposit
+        fn mint(&mut self, minted: u64) -> u64 {
+            let locked = locked_liquidity(self.lp_supply);
+            self.lp_supply += minted + locked;
+            self.pool.total_liquidity = self.pool.total_liquidity.checked_add(minted + locked).unwrap();
+            minted
+        }
+
+        fn burn(&mut self, liquidity: u64) {
+            self.lp_supply -= liquidity;
+            self.pool.total_liquidity = self.pool.total_liquidity.checked_sub(liquidity).unwrap();
+        }
+
+        // Combined reserves per LP token, as (reserve_a, reserve_b, supply)
+        fn backing(&self) -> (u128, u128, u128) {
+            let (a, b) = self.combined();
+            (a as u128, b as u128, self.lp_supply as u128)
+        }
+    }
+
+    // a1 / s1 >= a0 / s0 for both tokens
+    fn assert_backing_not_diluted(before: (u128, u128, u128), after: (u128, u128, u128)) {
+        if before.2 == 0 || after.2 == 0 {
+            return;
+        }
+        assert!(after.0 * before.2 >= before.0 * after.2, "token A backing per LP decreased");
+        assert!(after.1 * before.2 >= before.1 * after.2, "token B backing per LP decreased");
+    }
+
+    #[test]
+    fn remote_and_local_deposits_mint_the_same_lp() {
+        let mut local = Model::new();
+        let mut remote = Model::new();
+        local.deposit_local(1_000_000, 4_000_000);
+        remote.deposit_local(1_000_000, 4_000_000);
+
+        assert_eq!(local.deposit_local(250_000, 1_000_000), remote.deposit_remote(250_000, 1_000_000));
+        assert_eq!(local.combined(), remote.combined());
+        assert_eq!(remote.pool.remote_reserve_a, 250_000);
+        assert_eq!(remote.pool.remote_reserve_b, 1_000_000);
+    }
+
+    #[test]
+    fn first_deposit_can_come_from_the_remote_pool() {
+        let mut model = Model::new();
+        assert_eq!(model.deposit_remote(500_000, 500_000), 500_000 - Pool::MINIMUM_LIQUIDITY);
+        assert_eq!((model.vault_a, model.vault_b), (0, 0));
+        assert_eq!(model.combined(), (500_000, 500_000));
+        assert_eq!(model.pool.total_liquidity, model.lp_supply);
+    }
+
+    #[test]
+    fn first_deposit_mints_the_geometric_mean_and_locks_the_minimum() {
+        let mut model = Model::new();
+        // sqrt(1_000_000 * 4_000_000) = 2_000_000, whatever the ratio of the two tokens
+        assert_eq!(model.deposit_local(1_000_000, 4_000_000), 2_000_000 - Pool::MINIMUM_LIQUIDITY);
+        assert_eq!(model.lp_supply, 2_000_000);
+        assert_eq!(model.pool.total_liquidity, 2_000_000);
+
+        // Deposits that can't cover the locked liquidity are rejected
+        let mut small = Model::new();
+        assert_eq!(
+            small.try_deposit_local(1, 1).unwrap_err(),
+            ErrorCode::InsufficientInitialLiquidity.into()
+        );
+        assert_eq!(small.try_deposit_local(1_000, 1_000).unwrap_err(), ErrorCode::ZeroLiquidityMinted.into());
+        assert_eq!(small.try_deposit_local(1_001, 1_001).unwrap(), 1);
+    }
+
+    #[test]
+    fn mixed_deposits_and_withdrawals_keep_the_ledger_consistent() {
+        let mut model = Model::new();
+        let mut minted = 0u64;
+        let mut burned = 0u64;
+
+        let steps: [(u8, u64, u64); 12] = [
+            (0, 1_000_000, 2_000_000),
+            (1, 300_000, 600_000),
+            (0, 123_457, 999_999),
+            (1, 77_777, 155_555),
+            (2, 100_000, 0),
+            (3, 50_000, 0),
+            (4, 10_000, 20_000),
+            (1, 1_001, 2_003),
+            (0, 999_999, 3_000_000),
+            (3, 200_000, 0),
+            (2, 333_333, 0),
+            (4, 5_000, 10_000),
+        ];
+
+        for (op, x, y) in steps {
+            let before = model.backing();
+            match op {
+                0 => minted += model.deposit_local(x, y),
+                1 => minted += model.deposit_remote(x, y),
+                2 => {
+                    model.withdraw_local(x).unwrap();
+                    burned += x;
+                }
+                3 => {
+                    model.withdraw_remote(x).unwrap();
+                    burned += x;
+                }
+                _ => model.pay_remote_withdrawal(x, y),
+            }
+
+            assert_eq!(model.pool.total_liquidity, model.lp_supply);
+            assert_eq!(model.lp_supply, Pool::MINIMUM_LIQUIDITY + minted - burned);
+            assert_backing_not_diluted(before, model.backing());
+
+            let (combined_a, combined_b) = model.combined();
+            assert_eq!(combined_a, model.vault_a + model.pool.remote_reserve_a);
+            assert_eq!(combined_b, model.vault_b + model.pool.remote_reserve_b);
+        }
+    }
+
+    #[test]
+    fn withdrawals_are_limited_to_where_the_tokens_sit() {
+        let mut model = Model::new();
+        model.deposit_local(100_000, 100_000);
+        model.deposit_remote(900_000, 900_000);
+
+        // 50% of the supply is worth more than the local vaults hold
+        assert!(model.withdraw_local(500_000).is_err());
+        // and more than the remote ledger holds once it is mostly local
+        let mut local_heavy = Model::new();
+        local_heavy.deposit_local(900_000, 900_000);
+        local_heavy.deposit_remote(100_000, 100_000);
+        assert!(local_heavy.withdraw_remote(500_000).is_err());
+
+        model.withdraw_local(99_000).unwrap();
+        model.withdraw_remote(900_000).unwrap();
+        // Only the locked liquidity and what backs it are left
+        assert_eq!(model.combined(), (Pool::MINIMUM_LIQUIDITY, Pool::MINIMUM_LIQUIDITY));
+        assert_eq!(model.pool.total_liquidity, Pool::MINIMUM_LIQUIDITY);
+    }
+
+    #[test]
+    fn protocol_fees_and_donations_are_not_lp_backing() {
+        let mut model = Model::new();
+        model.deposit_local(10_000, 10_000);
+        model.deposit_remote(10_000, 10_000);
+        model.vault_a += 10;
+        model.pool.protocol_fee_a = 10;
+        model.donate(5_000, 7);
+        assert_eq!(model.combined(), (20_000, 20_000));
+    }
+
+    // The classic first-depositor attack: mint a single LP token, donate to the vaults so that
+    // token is worth a lot, and let the next deposit round down to (almost) nothing.
+    const DONATION: u64 = 1_000_000;
+    const VICTIM_DEPOSIT: u64 = 1_999_999;
+
+    #[test]
+    fn inflation_attack_worked_against_the_previous_accounting() {
+        // Previous behaviour: reserves read from the vault balances and the
+        // first deposit minting `amount_a` LP tokens
+        fn deposit(vault: &mut u64, lp_supply: &mut u64, amount: u64) -> u64 {
+            let minted = if *lp_supply == 0 { amount } else { amount * *lp_supply / *vault };
+            *vault += amount;
+            *lp_supply += minted;
+            minted
+        }
+        let (mut vault, mut lp_supply) = (0u64, 0u64);
+
+        let attacker_lp = deposit(&mut vault, &mut lp_supply, 1);
+        assert_eq!(attacker_lp, 1);
+        vault += DONATION;
+        let victim_lp = deposit(&mut vault, &mut lp_supply, VICTIM_DEPOSIT);
+        assert_eq!(victim_lp, 1);
+
+        // Each owns half the pool: the attacker walks away with part of the victim's deposit
+        let attacker_payout = vault * attacker_lp / lp_supply;
+        assert!(attacker_payout > 1 + DONATION);
+        assert!(vault - attacker_payout < VICTIM_DEPOSIT);
+    }
+
+    #[test]
+    fn inflation_attack_fails_with_locked_liquidity_and_tracked_reserves() {
+        let mut model = Model::new();
+        // A single LP token now needs a deposit above the locked liquidity
+        assert!(model.try_deposit_local(1, 1).is_err());
+        let attacker_lp = model.deposit_local(1_001, 1_001);
+        assert_eq!(attacker_lp, 1);
+
+        // The donation reaches the vaults but not the reserves, so the LP price doesn't move
+        model.donate(DONATION, DONATION);
+        let victim_lp = model.deposit_local(VICTIM_DEPOSIT, VICTIM_DEPOSIT);
+        assert_eq!(victim_lp, VICTIM_DEPOSIT);
+
+        // The attacker gets back their own deposit's share and loses the donation
+        let (attacker_a, attacker_b) = model.withdraw_local(attacker_lp).unwrap();
+        assert_eq!((attacker_a, attacker_b), (1, 1));
+        let (victim_a, victim_b) = model.withdraw_local(victim_lp).unwrap();
+        assert_eq!((victim_a, victim_b), (VICTIM_DEPOSIT, VICTIM_DEPOSIT));
+    }
+
+    #[test]
+    fn inflation_attack_is_unprofitable_even_if_the_donation_counted() {
+        // Same attack priced against the vault balances, to isolate the locked liquidity
+        let mut model = Model::new();
+        let attacker_lp = model.deposit_local(1_001, 1_001);
+        model.pool.deposit_reserves(DONATION, DONATION).unwrap();
+        let victim_lp = model.deposit_local(VICTIM_DEPOSIT, VICTIM_DEPOSIT);
+        assert!(victim_lp > 0);
+
+        // Most of the donation belongs to the locked liquidity, the attacker can't recover it
+        let (attacker_a, _) = model.withdraw_local(attacker_lp).unwrap();
+        assert!(attacker_a < 1_001 + DONATION);
+        assert!(attacker_a < DONATION / 100);
+    }
+
+    #[test]
+    fn pool_id_hashes_the_sorted_mints_and_the_fee_tier() {
+        let mint_a = Pubkey::new_from_array([0x01; 32]);
+        let mint_b = Pubkey::new_from_array([0x02; 32]);
+
+        // Layout Sui hashes too: "pool" || mint_a || mint_b || fee (u64 big-endian)
+        let mut preimage = b"pool".to_vec();
+        preimage.extend_from_slice(&[0x01; 32]);
+        preimage.extend_from_slice(&[0x02; 32]);
+        preimage.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 30]);
+        assert_eq!(Pool::derive_pool_id(&mint_a, &mint_b, 30), keccak::hash(&preimage).to_bytes());
+
+        // Each fee tier and mint order is a different pool
+        assert_ne!(Pool::derive_pool_id(&mint_a, &mint_b, 30), Pool::derive_pool_id(&mint_a, &mint_b, 5));
+        assert_ne!(Pool::derive_pool_id(&mint_a, &mint_b, 30), Pool::derive_pool_id(&mint_b, &mint_a, 30));
+    }
+
+    #[test]
+    fn size_matches_serialized_layout() {
+        assert_eq!(8 + Pool::default().try_to_vec().unwrap().len(), Pool::SIZE);
+    }
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::math::mul_div_floor;
+
+// Outflow cap for one token, scoped to a pool or to a source chain:
+//   pool:  [SEED, pool, mint]
+//   chain: [SEED, chain_id (le), mint]
+// Bridge-driven withdrawals draw from `available`, which refills linearly to `capacity`
+// over `window_seconds`, so at most `capacity` leaves in any rolling window.
+// A missing account means no cap for that scope.
+#[account]
+#[derive(Default)]
+pub struct RateLimit {
+    pub mint: Pubkey,           // Token the cap applies to
+    pub capacity: u64,          // Maximum outflow per window
+    pub window_seconds: i64,    // Length of the rolling window
+    pub release_delay: i64,     // Cooldown before an over-limit withdrawal can be released
+    pub available: u64,         // Outflow still allowed right now
+    pub last_refill_at: i64,    // Timestamp `available` was last brought up to date
+    pub bump: u8,               // PDA bump seed
+}
+
+impl RateLimit {
+    pub const SEED: &'static [u8] = b"rate_limit";
+
+    // Discriminator (8) + Pubkey (1) + u64 (1) + i64 (2) + u64 (1) + i64 (1) + u8 (1)
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Windows must be positive, release delays may be zero; both are bounded to 30 days
+    pub const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+
+    // Set (or change) the cap. A new cap starts full; an existing one keeps what was used.
+    pub fn configure(
+        &mut self,
+        capacity: u64,
+        window_seconds: i64,
+        release_delay: i64,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            (1..=Self::MAX_DURATION).contains(&window_seconds),
+            ErrorCode::InvalidRateLimit
+        );
+        require!(
+            (0..=Self::MAX_DURATION).contains(&release_delay),
+            ErrorCode::InvalidRateLimit
+        );
+
+        let used = if self.last_refill_at == 0 {
+            0
+        } else {
+            self.refill(now);
+            self.capacity.saturating_sub(self.available)
+        };
+
+        self.capacity = capacity;
+        self.window_seconds = window_seconds;
+        self.release_delay = release_delay;
+        self.available = capacity.saturating_sub(used);
+        self.last_refill_at = now;
+        Ok(())
+    }
+
+    // Bring `available` up to date: `capacity` per `window_seconds`, never above `capacity`
+    pub fn refill(&mut self, now: i64) {
+        if now <= self.last_refill_at {
+            return;
+        }
+        // A full window or more refills everything, so `elapsed` is capped at the window
+        let window = self.window_seconds.max(1) as u64;
+        let elapsed = (now.saturating_sub(self.last_refill_at) as u64).min(window);
+        let refilled = mul_div_floor(self.capacity, elapsed, window).unwrap_or(self.capacity);
+        self.available = self.available.saturating_add(refilled).min(self.capacity);
+        self.last_refill_at = now;
+    }
+
+    // Whether `amount` fits in the current window (call `refill` first)
+    pub fn allows(&self, amount: u64) -> bool {
+        amount <= self.available
+    }
+
+    pub fn consume(&mut self, amount: u64) -> Result<()> {
+        self.available = self.available.checked_sub(amount).ok_or(ErrorCode::RateLimitExceeded)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(capacity: u64, window_seconds: i64) -> RateLimit {
+        let mut limit = RateLimit::default();
+        limit.configure(capacity, window_seconds, 3_600, 1_000).unwrap();
+        limit
+    }
+
+    #[test]
+    fn new_limit_starts_full() {
+        let limit = limit(1_000, 86_400);
+        assert_eq!(limit.available, 1_000);
+        assert!(limit.allows(1_000));
+        assert!(!limit.allows(1_001));
+    }
+
+    #[test]
+    fn refills_linearly_over_the_window() {
+        let mut limit = limit(1_000, 100);
+        limit.consume(1_000).unwrap();
+
+        limit.refill(1_010);
+        assert_eq!(limit.available, 100);
+        limit.refill(1_060);
+        assert_eq!(limit.available, 600);
+        // Never above capacity, however long it has been
+        limit.refill(10_000_000);
+        assert_eq!(limit.available, 1_000);
+    }
+
+    #[test]
+    fn window_caps_total_outflow() {
+        let mut limit = limit(1_000, 100);
+        let mut total = 0u64;
+        // Drain whatever is available every second for one window
+        for now in 1_000..1_100 {
+            limit.refill(now);
+            let amount = limit.available;
+            limit.consume(amount).unwrap();
+            total += amount;
+        }
+        // The initial capacity plus what refilled during the window
+        assert!(total < 2 * 1_000);
+        assert!(limit.consume(1).is_err());
+    }
+
+    #[test]
+    fn clock_going_backwards_does_not_refill() {
+        let mut limit = limit(1_000, 100);
+        limit.consume(500).unwrap();
+        limit.refill(900);
+        assert_eq!(limit.available, 500);
+        assert_eq!(limit.last_refill_at, 1_000);
+    }
+
+    #[test]
+    fn reconfiguring_keeps_used_amount() {
+        let mut limit = limit(1_000, 100);
+        limit.consume(800).unwrap();
+        limit.configure(2_000, 100, 0, 1_000).unwrap();
+        assert_eq!(limit.available, 1_200);
+        limit.configure(500, 100, 0, 1_000).unwrap();
+        assert_eq!(limit.available, 0);
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        let mut limit = RateLimit::default();
+        assert!(limit.configure(1, 0, 0, 1).is_err());
+        assert!(limit.configure(1, RateLimit::MAX_DURATION + 1, 0, 1).is_err());
+        assert!(limit.configure(1, 1, -1, 1).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+};
+use anchor_spl::token_interface::Mint;
+use crate::errors::ErrorCode;
+
+// Token-2022 mint extensions.
+// Pools accept SPL Token and Token-2022 mints. Extensions that only carry metadata or change how
+// balances are displayed (interest-bearing) don't affect the raw amounts the pool works with.
+// Transfer fees do: the receiver gets less than was sent, so amounts are converted with the
+// helpers below. Extensions that let someone else block, run code on or take the vaults'
+// tokens are rejected when the pool is created.
+
+// Everything else, including extensions added to Token-2022 later, is rejected
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 9] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+// Reject mints with extensions a pool can't hold safely (transfer hooks, non-transferable,
+// permanent delegates, default-frozen accounts, confidential transfers, ...)
+pub fn require_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(()); // SPL Token mints have no extensions
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        if !SUPPORTED_MINT_EXTENSIONS.contains(&extension) {
+            msg!("Unsupported mint extension: {:?}", extension);
+            return err!(ErrorCode::UnsupportedMintExtension);
+        }
+    }
+    Ok(())
+}
+
+// Transfer fee the mint charges in the current epoch, if it has one
+fn current_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFee>> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(Some(*config.get_epoch_fee(Clock::get()?.epoch))),
+        Err(_) => Ok(None),
+    }
+}
+
+// Amount that arrives when `amount` of `mint` is transferred
+pub fn amount_received(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    net_of_fee(current_transfer_fee(mint)?.as_ref(), amount)
+}
+
+// Smallest amount of `mint` to transfer so that `received` arrives
+pub fn amount_to_send(mint: &InterfaceAccount<Mint>, received: u64) -> Result<u64> {
+    gross_of_fee(current_transfer_fee(mint)?.as_ref(), received)
+}
+
+fn net_of_fee(fee: Option<&TransferFee>, amount: u64) -> Result<u64> {
+    match fee {
+        Some(fee) => Ok(fee.calculate_post_fee_amount(amount).ok_or(ErrorCode::Overflow)?),
+        None => Ok(amount),
+    }
+}
+
+fn gross_of_fee(fee: Option<&TransferFee>, received: u64) -> Result<u64> {
+    let Some(fee) = fee else {
+        return Ok(received);
+    };
+    // No amount exists when it would overflow, or for a 100% fee
+    let gross = fee.calculate_pre_fee_amount(received).ok_or(ErrorCode::Overflow)?;
+    require!(fee.calculate_post_fee_amount(gross) == Some(received), ErrorCode::Overflow);
+    Ok(gross)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn amounts_without_a_fee_are_unchanged() {
+        assert_eq!(net_of_fee(None, 1_000).unwrap(), 1_000);
+        assert_eq!(gross_of_fee(None, 1_000).unwrap(), 1_000);
+        assert_eq!(gross_of_fee(Some(&fee(0, 0)), 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn fees_round_up_and_are_capped() {
+        // 1% rounded up: 1 on 1..=100, 2 on 101
+        let one_percent = fee(100, u64::MAX);
+        assert_eq!(net_of_fee(Some(&one_percent), 1).unwrap(), 0);
+        assert_eq!(net_of_fee(Some(&one_percent), 101).unwrap(), 99);
+        assert_eq!(net_of_fee(Some(&one_percent), 10_000).unwrap(), 9_900);
+
+        let capped = fee(100, 5);
+        assert_eq!(net_of_fee(Some(&capped), 1_000_000).unwrap(), 999_995);
+        assert_eq!(gross_of_fee(Some(&capped), 999_995).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn gross_amounts_deliver_exactly_the_requested_net() {
+        for (basis_points, maximum_fee) in [(1, u64::MAX), (30, 1_000), (250, u64::MAX), (9_999, 7)] {
+            let fee = fee(basis_points, maximum_fee);
+            for received in [0, 1, 2, 99, 100, 12_345, 1_000_000, u32::MAX as u64] {
+                let gross = gross_of_fee(Some(&fee), received).unwrap();
+                assert_eq!(net_of_fee(Some(&fee), gross).unwrap(), received);
+                // The smallest such amount
+                assert!(gross == 0 || net_of_fee(Some(&fee), gross - 1).unwrap() < received);
+            }
+        }
+        // A 100% fee can't deliver anything
+        assert!(gross_of_fee(Some(&fee(10_000, u64::MAX)), 1).is_err());
+        assert!(gross_of_fee(Some(&fee(100, u64::MAX)), u64::MAX).is_err());
+    }
+}
@@ -12,10 +12,39 @@ import {
   Account,
   getMint,
   burn,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  NATIVE_MINT,
 } from "@solana/spl-token";
-import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL, Transaction } from "@solana/web3.js";
 import { assert } from "chai";
-import { randomBytes } from "crypto";
+import { keccak256 } from "@certusone/wormhole-sdk";
+
+// Helper function to decode the Anchor events emitted by a confirmed transaction
+async function parseEvents(program: Program<LiquidityPoolProgram>, signature: string) {
+  const tx = await program.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+  return Array.from(parser.parseLogs(tx.meta.logMessages));
+}
+
+// Pools take their mints in canonical (byte-wise ascending) order
+function sortMints(a: PublicKey, b: PublicKey): [PublicKey, PublicKey] {
+  return Buffer.compare(a.toBuffer(), b.toBuffer()) < 0 ? [a, b] : [b, a];
+}
+
+// Pool PDA of a pair and fee tier
+function poolAddress(programId: PublicKey, mintA: PublicKey, mintB: PublicKey, fee: BN): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer(), fee.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+}
 
 // Helper function to create a new mint
 async function createTestMint(provider: anchor.AnchorProvider): Promise<PublicKey> {
@@ -80,6 +109,7 @@ describe("liquidity_pool_program", () => {
   let tokenAAccountBump: number;
   let tokenBAccountBump: number;
   let lpMintBump: number;
+  let configPda: PublicKey;
 
   let userTokenAAccount: PublicKey;
   let userTokenBAccount: PublicKey;
@@ -87,7 +117,7 @@ describe("liquidity_pool_program", () => {
 
   const user = provider.wallet.payer; // Use the provider's wallet as the user for simplicity
   const feePercentage = new BN(30); // 0.3% fee (30 basis points)
-  const poolId = randomBytes(32); // Generate a random pool ID
+  const constantProduct = { constantProduct: {} }; // Curve type, takes no amplification
 
   before(async () => {
     // Airdrop SOL to the user wallet if needed (useful for localnet/devnet)
@@ -98,14 +128,13 @@ describe("liquidity_pool_program", () => {
     await provider.connection.confirmTransaction(airdropSignature);
 
     // Create mints for Token A and Token B
-    tokenAMint = await createTestMint(provider);
-    tokenBMint = await createTestMint(provider);
+    [tokenAMint, tokenBMint] = sortMints(await createTestMint(provider), await createTestMint(provider));
+
+    // Derive the global config PDA
+    [configPda] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
 
     // Derive PDAs for the pool and its components
-    [poolPda, poolBump] = PublicKey.findProgramAddressSync(
-      [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer()],
-      program.programId
-    );
+    [poolPda, poolBump] = poolAddress(program.programId, tokenAMint, tokenBMint, feePercentage);
     [poolAuthorityPda, authorityBump] = PublicKey.findProgramAddressSync(
       [Buffer.from("authority"), poolPda.toBuffer()],
       program.programId
@@ -133,20 +162,46 @@ describe("liquidity_pool_program", () => {
     await mintTokens(provider, tokenBMint, userTokenBAccount, BigInt(1000 * 1e6)); // 1000 Token B
   });
 
+  it("Initializes the program config", async () => {
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    await program.methods
+      .initializeConfig([new BN(1), new BN(5), feePercentage, new BN(100)], new BN(2000))
+      .accounts({
+        admin: user.publicKey,
+        config: configPda,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const config = await program.account.config.fetch(configPda);
+    assert.ok(config.admin.equals(user.publicKey));
+    assert.equal(config.defaultProtocolFeeShare.toNumber(), 2000);
+    assert.isFalse(config.paused);
+  });
+
   it("Creates a new liquidity pool", async () => {
     const tx = await program.methods
-      .createPool(feePercentage, Buffer.from(poolId))
+      .createPool(feePercentage, constantProduct, new BN(0))
       .accounts({
         pool: poolPda,
+        config: configPda,
         poolAuthority: poolAuthorityPda,
         tokenAMint: tokenAMint,
         tokenBMint: tokenBMint,
         tokenAAccount: tokenAAccountPda,
         tokenBAccount: tokenBAccountPda,
         lpMint: lpMint,
-        payer: user.publicKey,
+        creator: user.publicKey,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
@@ -161,7 +216,11 @@ describe("liquidity_pool_program", () => {
     assert.ok(poolAccount.tokenBAccount.equals(tokenBAccountPda));
     assert.ok(poolAccount.lpMint.equals(lpMint));
     assert.equal(poolAccount.feePercentage.toNumber(), feePercentage.toNumber());
-    assert.deepEqual(poolAccount.poolId, Array.from(poolId)); // Compare buffer/array content
+    // keccak256("pool" || token_a_mint || token_b_mint || fee u64 BE), what Sui derives too
+    const expectedPoolId = keccak256(
+      Buffer.concat([Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), feePercentage.toArrayLike(Buffer, "be", 8)])
+    );
+    assert.deepEqual(poolAccount.poolId, Array.from(expectedPoolId)); // Compare buffer/array content
     assert.equal(poolAccount.bump, poolBump);
     assert.equal(poolAccount.authorityBump, authorityBump);
     assert.equal(poolAccount.tokenAAccountBump, tokenAAccountBump);
@@ -210,6 +269,7 @@ describe("liquidity_pool_program", () => {
       .accounts({
         user: user.publicKey,
         pool: poolPda,
+        config: configPda,
         poolAuthority: poolAuthorityPda,
         tokenAMint: tokenAMint,
         tokenBMint: tokenBMint,
@@ -220,6 +280,8 @@ describe("liquidity_pool_program", () => {
         userTokenBAccount: userTokenBAccount, // User's source account
         userLpTokenAccount: userLpTokenAccount, // User's destination LP account
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
@@ -242,9 +304,14 @@ describe("liquidity_pool_program", () => {
     // First liquidity addition should mint sqrt(amountA * amountB) LP tokens (scaled by decimals)
     // Here, 100 * 100 = 10000, sqrt(10000) = 100. Scaled: 100 * 1e6
     const expectedLpMinted = new BN(100 * 1e6); // This is already BN
+    // of which MINIMUM_LIQUIDITY is locked in the pool's own LP account forever
+    const minimumLiquidity = new BN(1000);
+    const [lockedLpAccount] = PublicKey.findProgramAddressSync([Buffer.from("locked_lp"), poolPda.toBuffer()], program.programId);
+    const lockedLpBalance = new BN((await getAccount(provider.connection, lockedLpAccount)).amount.toString());
     assert.ok(finalLpSupply.sub(initialLpSupply).eq(expectedLpMinted), `LP supply mismatch: ${finalLpSupply.sub(initialLpSupply)} vs ${expectedLpMinted}`);
     assert.ok(finalUserLpBalance.gt(new BN(0)), "User should receive LP tokens"); // Use BN comparison
-    assert.ok(finalUserLpBalance.eq(expectedLpMinted), `User LP balance mismatch: ${finalUserLpBalance} vs ${expectedLpMinted}`);
+    assert.ok(finalUserLpBalance.eq(expectedLpMinted.sub(minimumLiquidity)), `User LP balance mismatch: ${finalUserLpBalance} vs ${expectedLpMinted}`);
+    assert.ok(lockedLpBalance.eq(minimumLiquidity), `Locked LP mismatch: ${lockedLpBalance}`);
 
 
     // Verify token movements
@@ -288,6 +355,7 @@ describe("liquidity_pool_program", () => {
       .accounts({
         user: user.publicKey,
         pool: poolPda,
+        config: configPda,
         poolAuthority: poolAuthorityPda,
         tokenAAccount: tokenAAccountPda, // Pool's vault
         tokenBAccount: tokenBAccountPda, // Pool's vault
@@ -296,6 +364,10 @@ describe("liquidity_pool_program", () => {
         userTokenBAccount: userTokenBAccount, // User's destination account
         userLpTokenAccount: userLpTokenAccount, // User's source LP account
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
@@ -341,5 +413,596 @@ describe("liquidity_pool_program", () => {
 
   });
 
-  // TODO: Add tests for process_vaa (more complex, requires mocking/setting up Wormhole state)
+  it("Swaps token A for token B", async () => {
+    const amountIn = new BN(10 * 1e6); // 10 Token A
+
+    const initialPoolAccount = await program.account.pool.fetch(poolPda);
+    const reserveA = new BN((await getAccount(provider.connection, tokenAAccountPda)).amount.toString());
+    const reserveB = new BN((await getAccount(provider.connection, tokenBAccountPda)).amount.toString());
+    const initialUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const initialUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+
+    // Expected output: fee rounded up, then x * y = k on the remaining input
+    const fee = amountIn.mul(feePercentage).add(new BN(9999)).div(new BN(10000));
+    const amountInAfterFee = amountIn.sub(fee);
+    const expectedOut = reserveB.mul(amountInAfterFee).div(reserveA.add(amountInAfterFee));
+    const expectedProtocolFee = fee.mul(new BN(2000)).div(new BN(10000));
+
+    const tx = await program.methods
+      .swap(amountIn, expectedOut, true)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config: configPda,
+        poolAuthority: poolAuthorityPda,
+        tokenAAccount: tokenAAccountPda,
+        tokenBAccount: tokenBAccountPda,
+        userTokenA: userTokenAAccount,
+        userTokenB: userTokenBAccount,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    console.log("Swap transaction signature", tx);
+
+    // The Swap event carries the reserves around the swap
+    const swapEvent = (await parseEvents(program, tx)).find((e) => e.name.toLowerCase() === "swap");
+    assert.ok(swapEvent, "Swap event not emitted");
+    const { before, after } = swapEvent.data as any;
+    assert.ok(swapEvent.data.amountIn.eq(amountIn));
+    assert.ok(swapEvent.data.amountOut.eq(expectedOut));
+    assert.ok(before.reserveA.eq(reserveA.sub(initialPoolAccount.protocolFeeA)));
+    assert.ok(after.reserveA.eq(before.reserveA.add(amountIn).sub(expectedProtocolFee)));
+    assert.ok(after.reserveB.eq(before.reserveB.sub(expectedOut)));
+    assert.ok(after.lpSupply.eq(before.lpSupply));
+
+    const finalPoolAccount = await program.account.pool.fetch(poolPda);
+    const finalUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const finalUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+
+    assert.ok(initialUserA.sub(finalUserA).eq(amountIn), "Incorrect Token A taken from user");
+    assert.ok(finalUserB.sub(initialUserB).eq(expectedOut), "Incorrect Token B sent to user");
+    assert.ok(
+      finalPoolAccount.protocolFeeA.sub(initialPoolAccount.protocolFeeA).eq(expectedProtocolFee),
+      "Incorrect protocol fee accrued"
+    );
+
+    // Asking for more than the curve can give must fail with SlippageExceeded
+    try {
+      await program.methods
+        .swap(amountIn, reserveB, true)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: configPda,
+          poolAuthority: poolAuthorityPda,
+          tokenAAccount: tokenAAccountPda,
+          tokenBAccount: tokenBAccountPda,
+          userTokenA: userTokenAAccount,
+          userTokenB: userTokenBAccount,
+          tokenAMint: tokenAMint,
+          tokenBMint: tokenBMint,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Swap should have failed on slippage");
+    } catch (err) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+  });
+
+  it("Swaps token B for an exact amount of token A", async () => {
+    const amountOut = new BN(5 * 1e6); // 5 Token A
+
+    const poolAccount = await program.account.pool.fetch(poolPda);
+    const reserveA = new BN((await getAccount(provider.connection, tokenAAccountPda)).amount.toString()).sub(poolAccount.protocolFeeA);
+    const reserveB = new BN((await getAccount(provider.connection, tokenBAccountPda)).amount.toString()).sub(poolAccount.protocolFeeB);
+    const initialUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const initialUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+
+    // Required input, rounded up twice in the pool's favour
+    const denominator = reserveA.sub(amountOut);
+    const amountInAfterFee = reserveB.mul(amountOut).add(denominator.subn(1)).div(denominator);
+    const feeComplement = new BN(10000).sub(feePercentage);
+    const expectedIn = amountInAfterFee.mul(new BN(10000)).add(feeComplement.subn(1)).div(feeComplement);
+
+    const swapAccounts = {
+      user: user.publicKey,
+      pool: poolPda,
+      config: configPda,
+      poolAuthority: poolAuthorityPda,
+      tokenAAccount: tokenAAccountPda,
+      tokenBAccount: tokenBAccountPda,
+      userTokenA: userTokenAAccount,
+      userTokenB: userTokenBAccount,
+      tokenAMint: tokenAMint,
+      tokenBMint: tokenBMint,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // A maximum input one unit below the quote must be rejected
+    try {
+      await program.methods.swapExactOut(amountOut, expectedIn.subn(1), false).accounts(swapAccounts).rpc();
+      assert.fail("Swap should have failed on slippage");
+    } catch (err) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+
+    await program.methods.swapExactOut(amountOut, expectedIn, false).accounts(swapAccounts).rpc();
+
+    const finalUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const finalUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+    assert.ok(finalUserA.sub(initialUserA).eq(amountOut), "Incorrect Token A sent to user");
+    assert.ok(initialUserB.sub(finalUserB).eq(expectedIn), "Incorrect Token B taken from user");
+  });
+
+  it("Records price observations and reports TWAPs", async () => {
+    const [oraclePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("oracle"), poolPda.toBuffer()],
+      program.programId
+    );
+    const priceAccounts = {
+      pool: poolPda,
+      tokenAAccount: tokenAAccountPda,
+      tokenBAccount: tokenBAccountPda,
+      oracle: oraclePda,
+    };
+
+    await program.methods
+      .initializeOracle(16)
+      .accounts({ payer: user.publicKey, ...priceAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    // Every swap appends an observation of the prices before the swap, so the oracle can't be left out
+    const swapAccounts = {
+      user: user.publicKey,
+      pool: poolPda,
+      config: configPda,
+      poolAuthority: poolAuthorityPda,
+      tokenAAccount: tokenAAccountPda,
+      tokenBAccount: tokenBAccountPda,
+      userTokenA: userTokenAAccount,
+      userTokenB: userTokenBAccount,
+      oracle: oraclePda,
+      tokenAMint: tokenAMint,
+      tokenBMint: tokenBMint,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+    };
+    try {
+      await program.methods.swap(new BN(1e6), new BN(0), true).accounts({ ...swapAccounts, oracle: null }).rpc();
+      assert.fail("Swap without the pool's oracle should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "OracleRequired");
+    }
+    await program.methods.swap(new BN(1e6), new BN(0), true).accounts(swapAccounts).rpc();
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    // The last second only saw the post-swap reserves, so its TWAP is the spot price (UQ64.64)
+    const poolAccount = await program.account.pool.fetch(poolPda);
+    const reserveA = new BN((await getAccount(provider.connection, tokenAAccountPda)).amount.toString()).sub(poolAccount.protocolFeeA);
+    const reserveB = new BN((await getAccount(provider.connection, tokenBAccountPda)).amount.toString()).sub(poolAccount.protocolFeeB);
+    const [twap] = await program.methods.observe([1]).accounts(priceAccounts).view();
+    assert.equal(twap.secondsAgo, 1);
+    assert.ok(twap.priceA.eq(reserveB.shln(64).div(reserveA)), "TWAP should match the spot price");
+    assert.ok(twap.priceB.eq(reserveA.shln(64).div(reserveB)), "Inverse TWAP should match the spot price");
+
+    // Windows reaching back before the first observation are rejected
+    try {
+      await program.methods.observe([1_000_000]).accounts(priceAccounts).view();
+      assert.fail("Window older than the oracle should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "ObservationTooOld");
+    }
+  });
+
+  it("Collects protocol fees to the fee recipient", async () => {
+    const poolAccount = await program.account.pool.fetch(poolPda);
+    assert.ok(poolAccount.feeRecipient.equals(user.publicKey), "Creator should be the initial fee recipient");
+    assert.ok(poolAccount.protocolFeeA.gt(new BN(0)), "Swaps should have accrued protocol fees");
+
+    const initialUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const initialUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+
+    await program.methods
+      .collectProtocolFees()
+      .accounts({
+        collector: user.publicKey,
+        pool: poolPda,
+        config: configPda,
+        poolAuthority: poolAuthorityPda,
+        tokenAAccount: tokenAAccountPda,
+        tokenBAccount: tokenBAccountPda,
+        recipientTokenA: userTokenAAccount,
+        recipientTokenB: userTokenBAccount,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const finalPoolAccount = await program.account.pool.fetch(poolPda);
+    const finalUserA = new BN((await getAccount(provider.connection, userTokenAAccount)).amount.toString());
+    const finalUserB = new BN((await getAccount(provider.connection, userTokenBAccount)).amount.toString());
+    assert.ok(finalPoolAccount.protocolFeeA.isZero() && finalPoolAccount.protocolFeeB.isZero(), "Counters should be reset");
+    assert.ok(finalUserA.sub(initialUserA).eq(poolAccount.protocolFeeA), "Incorrect Token A fees collected");
+    assert.ok(finalUserB.sub(initialUserB).eq(poolAccount.protocolFeeB), "Incorrect Token B fees collected");
+  });
+
+  it("Pauses and unpauses the pool", async () => {
+    const statusAccounts = { admin: user.publicKey, config: configPda, pool: poolPda };
+
+    await program.methods.setPoolStatus({ paused: {} }).accounts(statusAccounts).rpc();
+    let poolAccount = await program.account.pool.fetch(poolPda);
+    assert.deepEqual(poolAccount.status, { paused: {} });
+
+    // Swaps are blocked while paused
+    try {
+      await program.methods
+        .swap(new BN(1e6), new BN(0), true)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: configPda,
+          poolAuthority: poolAuthorityPda,
+          tokenAAccount: tokenAAccountPda,
+          tokenBAccount: tokenBAccountPda,
+          userTokenA: userTokenAAccount,
+          userTokenB: userTokenBAccount,
+          tokenAMint: tokenAMint,
+          tokenBMint: tokenBMint,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Swap should have failed on a paused pool");
+    } catch (err) {
+      assert.include(err.toString(), "PoolPaused");
+    }
+
+    await program.methods.setPoolStatus({ active: {} }).accounts(statusAccounts).rpc();
+    poolAccount = await program.account.pool.fetch(poolPda);
+    assert.deepEqual(poolAccount.status, { active: {} });
+  });
+
+  it("Lets LPs withdraw while the program is paused", async () => {
+    const pauseTx = await program.methods
+      .updateConfig(null, null, null, true)
+      .accounts({ admin: user.publicKey, config: configPda })
+      .rpc({ commitment: "confirmed" });
+    const configEvent = (await parseEvents(program, pauseTx)).find((e) => e.name.toLowerCase() === "configupdated");
+    assert.ok(configEvent, "ConfigUpdated event not emitted");
+    assert.isTrue((configEvent.data as any).paused);
+
+    // Deposits go through the pause check, withdrawals do not
+    const lpBefore = (await getAccount(provider.connection, userLpTokenAccount)).amount;
+    await program.methods
+      .removeLiquidity(new BN(1e6), new BN(0), new BN(0))
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        userTokenA: userTokenAAccount,
+        userTokenB: userTokenBAccount,
+        userWsolAccount: null,
+        userLpTokenAccount: userLpTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const lpAfter = (await getAccount(provider.connection, userLpTokenAccount)).amount;
+    assert.equal(lpBefore - lpAfter, BigInt(1e6));
+
+    await program.methods.updateConfig(null, null, null, false).accounts({ admin: user.publicKey, config: configPda }).rpc();
+  });
+
+  it("Adds and removes liquidity with native SOL", async () => {
+    // SOL / token B pool; the user pays and receives lamports instead of holding wSOL
+    const [mintA, mintB] = sortMints(NATIVE_MINT, tokenBMint);
+    const solIsA = mintA.equals(NATIVE_MINT);
+    const [solPool] = poolAddress(program.programId, mintA, mintB, feePercentage);
+    const [solLpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), solPool.toBuffer()],
+      program.programId
+    );
+    const [userWsolAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("wsol"), user.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .createPool(feePercentage, constantProduct, new BN(0))
+      .accounts({
+        pool: solPool,
+        config: configPda,
+        tokenAMint: mintA,
+        tokenBMint: mintB,
+        creator: user.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const liquidityAccounts = {
+      user: user.publicKey,
+      pool: solPool,
+      config: configPda,
+      tokenAMint: mintA,
+      tokenBMint: mintB,
+      // The native side is left out and wrapped by the instruction
+      userTokenA: solIsA ? null : userTokenBAccount,
+      userTokenB: solIsA ? userTokenBAccount : null,
+      userWsolAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+    };
+    const [solVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from(solIsA ? "token_a" : "token_b"), solPool.toBuffer()],
+      program.programId
+    );
+
+    const amountSol = new BN(LAMPORTS_PER_SOL / 10);
+    const lamportsBefore = await provider.connection.getBalance(user.publicKey);
+    await program.methods
+      .addLiquidity(
+        solIsA ? amountSol : new BN(10 * 1e6),
+        solIsA ? new BN(10 * 1e6) : amountSol,
+        new BN(0),
+        new BN(0)
+      )
+      .accounts(liquidityAccounts)
+      .rpc();
+
+    assert.equal((await getAccount(provider.connection, solVault)).amount, BigInt(amountSol.toString()));
+    // The temporary wSOL account is closed again, its rent goes back to the user
+    assert.isNull(await provider.connection.getAccountInfo(userWsolAccount));
+    const lamportsAfterDeposit = await provider.connection.getBalance(user.publicKey);
+    assert.isAtLeast(lamportsBefore - lamportsAfterDeposit, amountSol.toNumber());
+    assert.isBelow(lamportsBefore - lamportsAfterDeposit, amountSol.toNumber() + LAMPORTS_PER_SOL / 100);
+
+    // Burning everything the user holds pays the SOL side out as lamports
+    const userLp = await anchor.utils.token.associatedAddress({ mint: solLpMint, owner: user.publicKey });
+    const lpBalance = (await getAccount(provider.connection, userLp)).amount;
+    await program.methods
+      .removeLiquidity(new BN(lpBalance.toString()), new BN(0), new BN(0))
+      .accounts({ ...liquidityAccounts, userLpTokenAccount: userLp })
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(userWsolAccount));
+    const lamportsAfterWithdrawal = await provider.connection.getBalance(user.publicKey);
+    const vaultLeft = Number((await getAccount(provider.connection, solVault)).amount);
+    // Everything but the share backing the locked minimum liquidity comes back, minus tx fees
+    assert.isAbove(lamportsAfterWithdrawal - lamportsAfterDeposit, amountSol.toNumber() - vaultLeft - LAMPORTS_PER_SOL / 100);
+  });
+
+  it("Creates one pool per fee tier for the same pair", async () => {
+    const createPoolAccounts = (fee: BN) => ({
+      pool: poolAddress(program.programId, tokenAMint, tokenBMint, fee)[0],
+      config: configPda,
+      tokenAMint: tokenAMint,
+      tokenBMint: tokenBMint,
+      creator: user.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    });
+
+    // The 30 bps pool exists already; 5 bps is another configured tier and gets its own pool
+    const lowFee = new BN(5);
+    await program.methods.createPool(lowFee, constantProduct, new BN(0)).accounts(createPoolAccounts(lowFee)).rpc();
+    const lowFeePool = await program.account.pool.fetch(createPoolAccounts(lowFee).pool);
+    assert.equal(lowFeePool.feePercentage.toNumber(), 5);
+    assert.notDeepEqual(lowFeePool.poolId, (await program.account.pool.fetch(poolPda)).poolId);
+
+    // Fees outside the configured tiers are rejected, whether in range or not
+    for (const [fee, error] of [
+      [new BN(25), "FeeTierNotAllowed"],
+      [new BN(10_001), "InvalidFeePercentage"],
+    ] as [BN, string][]) {
+      try {
+        await program.methods.createPool(fee, constantProduct, new BN(0)).accounts(createPoolAccounts(fee)).rpc();
+        assert.fail(`Pool creation should have failed for a ${fee} bps fee`);
+      } catch (err) {
+        assert.include(err.toString(), error);
+      }
+    }
+  });
+
+  it("Rejects mints in reversed order", async () => {
+    const [pool] = poolAddress(program.programId, tokenBMint, tokenAMint, feePercentage);
+    try {
+      await program.methods
+        .createPool(feePercentage, constantProduct, new BN(0))
+        .accounts({
+          pool,
+          config: configPda,
+          tokenAMint: tokenBMint,
+          tokenBMint: tokenAMint,
+          creator: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      assert.fail("Pool creation should have failed for reversed mints");
+    } catch (err) {
+      assert.include(err.toString(), "MintsNotSorted");
+    }
+  });
+
+  it("Rejects Token-2022 mints with unsupported extensions", async () => {
+    // Non-transferable tokens could never leave the vaults
+    const mint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.NonTransferable]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: user.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeNonTransferableMintInstruction(mint.publicKey, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, 6, user.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [mint]
+    );
+
+    const [mintA, mintB] = sortMints(mint.publicKey, tokenBMint);
+    const programOf = (m: PublicKey) => (m.equals(mint.publicKey) ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID);
+    const [pool] = poolAddress(program.programId, mintA, mintB, feePercentage);
+    try {
+      await program.methods
+        .createPool(feePercentage, constantProduct, new BN(0))
+        .accounts({
+          pool,
+          config: configPda,
+          tokenAMint: mintA,
+          tokenBMint: mintB,
+          creator: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenAProgram: programOf(mintA),
+          tokenBProgram: programOf(mintB),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      assert.fail("Pool creation should have failed for a non-transferable mint");
+    } catch (err) {
+      assert.include(err.toString(), "UnsupportedMintExtension");
+    }
+  });
+
+  it("Creates a StableSwap pool and swaps close to 1:1 across decimals", async () => {
+    // Pegged pair with different decimals: 6 like USDC, 9 like a wrapped stablecoin
+    const [mintA, mintB] = sortMints(
+      await createTestMint(provider),
+      await createMint(provider.connection, user, user.publicKey, null, 9)
+    );
+    const unitsA = (await getMint(provider.connection, mintA)).decimals === 6 ? 1e6 : 1e9;
+    const unitsB = unitsA === 1e6 ? 1e9 : 1e6;
+    const fee = new BN(5);
+    const [stablePool] = poolAddress(program.programId, mintA, mintB, fee);
+    const userA = await createTestTokenAccount(provider, mintA, user.publicKey);
+    const userB = await createTestTokenAccount(provider, mintB, user.publicKey);
+    await mintTokens(provider, mintA, userA, BigInt(1_000_000 * unitsA));
+    await mintTokens(provider, mintB, userB, BigInt(1_000_000 * unitsB));
+
+    const createAccounts = {
+      pool: stablePool,
+      config: configPda,
+      tokenAMint: mintA,
+      tokenBMint: mintB,
+      creator: user.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    };
+    // Constant-product pools take no amplification
+    try {
+      await program.methods.createPool(fee, constantProduct, new BN(100)).accounts(createAccounts).rpc();
+      assert.fail("Pool creation should have failed with an amplification");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmplification");
+    }
+    await program.methods.createPool(fee, { stableSwap: {} }, new BN(100)).accounts(createAccounts).rpc();
+
+    let poolAccount = await program.account.pool.fetch(stablePool);
+    assert.deepEqual(poolAccount.curveType, { stableSwap: {} });
+    assert.equal(poolAccount.ampTarget.toNumber(), 100);
+    assert.deepEqual([poolAccount.tokenADecimals, poolAccount.tokenBDecimals], [Math.log10(unitsA), Math.log10(unitsB)]);
+
+    const accounts = {
+      user: user.publicKey,
+      pool: stablePool,
+      config: configPda,
+      tokenAMint: mintA,
+      tokenBMint: mintB,
+      userTokenA: userA,
+      userTokenB: userB,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+    };
+    await program.methods
+      .addLiquidity(new BN(100_000 * unitsA), new BN(100_000 * unitsB), new BN(0), new BN(0))
+      .accounts(accounts)
+      .rpc();
+    // The first deposit mints the invariant D, in the larger decimals, minus the locked minimum
+    poolAccount = await program.account.pool.fetch(stablePool);
+    assert.equal(poolAccount.totalLiquidity.toString(), new BN(200_000).mul(new BN(1e9)).toString());
+
+    // 1,000 A out of 100,000 a side: a constant-product pool would pay about 990 B
+    const balanceB = async () => Number((await getAccount(provider.connection, userB)).amount);
+    const before = await balanceB();
+    await program.methods.swap(new BN(1_000 * unitsA), new BN(0), true).accounts(accounts).rpc();
+    const received = ((await balanceB()) - before) / unitsB;
+    assert.isAbove(received, 999.4);
+    assert.isBelow(received, 1_000);
+  });
+
+  it("Ramps the amplification of StableSwap pools only, within bounds", async () => {
+    const stablePools = await program.account.pool.all();
+    const stablePool = stablePools.find((p) => "stableSwap" in p.account.curveType);
+    assert.ok(stablePool, "StableSwap pool missing");
+    const accounts = { admin: user.publicKey, config: configPda, pool: stablePool.publicKey };
+    const now = Math.floor(Date.now() / 1000);
+    const day = 86_400;
+
+    // More than a 10x change, or shorter than a day
+    for (const [target, end] of [
+      [new BN(1_001), new BN(now + 2 * day)],
+      [new BN(200), new BN(now + day / 2)],
+    ] as [BN, BN][]) {
+      try {
+        await program.methods.rampAmplification(target, end).accounts(accounts).rpc();
+        assert.fail("Ramp should have been rejected");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidAmplificationRamp");
+      }
+    }
+
+    await program.methods.rampAmplification(new BN(200), new BN(now + 2 * day)).accounts(accounts).rpc();
+    let poolAccount = await program.account.pool.fetch(stablePool.publicKey);
+    assert.equal(poolAccount.ampInitial.toNumber(), 100);
+    assert.equal(poolAccount.ampTarget.toNumber(), 200);
+
+    // Stopping freezes the amplification where the ramp got to, barely past the start
+    await program.methods.stopRampAmplification().accounts(accounts).rpc();
+    poolAccount = await program.account.pool.fetch(stablePool.publicKey);
+    assert.equal(poolAccount.ampTarget.toNumber(), poolAccount.ampInitial.toNumber());
+    assert.isBelow(poolAccount.ampTarget.toNumber(), 101);
+    assert.equal(poolAccount.ampRampEnd.toNumber(), poolAccount.ampRampStart.toNumber());
+
+    // Constant-product pools have nothing to ramp
+    try {
+      await program.methods
+        .rampAmplification(new BN(200), new BN(now + 2 * day))
+        .accounts({ ...accounts, pool: poolPda })
+        .rpc();
+      assert.fail("Ramp should have been rejected on a constant-product pool");
+    } catch (err) {
+      assert.include(err.toString(), "NotStableSwapPool");
+    }
+  });
+
+  // process_vaa is covered in process_vaa.ts against a locally initialized Wormhole core bridge
 });
@@ -0,0 +1,842 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { LiquidityPoolProgram } from "../target/types/liquidity_pool_program";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { CHAIN_ID_SUI, keccak256, parseVaa, postVaaSolana } from "@certusone/wormhole-sdk";
+import { MockEmitter, MockGuardians } from "@certusone/wormhole-sdk/lib/cjs/mock";
+import {
+  createInitializeInstruction,
+  derivePostedVaaKey,
+  getPostedMessage,
+} from "@certusone/wormhole-sdk/lib/cjs/solana/wormhole";
+import { assert } from "chai";
+import { randomBytes } from "crypto";
+
+// The Wormhole core bridge is loaded at genesis (see Anchor.toml); `yarn test` dumps it from mainnet first
+const CORE_BRIDGE_PID = new PublicKey("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+// Locally generated guardian set (index 0) the core bridge is initialized with
+const GUARDIAN_PRIVATE_KEY = "cfb12303a19cde580bb4dd771639b0d26bc68353645571a8cff516ab2ee113a0";
+
+// Pools take their mints in canonical (byte-wise ascending) order
+function sortMints(a: PublicKey, b: PublicKey): [PublicKey, PublicKey] {
+  return Buffer.compare(a.toBuffer(), b.toBuffer()) < 0 ? [a, b] : [b, a];
+}
+
+describe("process_vaa guardian verification", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .LiquidityPoolProgram as Program<LiquidityPoolProgram>;
+  const payer = provider.wallet.payer;
+
+  const guardians = new MockGuardians(0, [GUARDIAN_PRIVATE_KEY]);
+  const suiEmitter = new MockEmitter(randomBytes(32).toString("hex"), CHAIN_ID_SUI);
+
+  let accounts: Record<string, PublicKey>;
+  let suiForeignEmitter: PublicKey;
+  // The Sui pool our pool is linked to
+  const suiPool = randomBytes(32);
+  // Our pool's pool_id, which Sui puts in every liquidity message
+  let poolId: Buffer;
+
+  // Sign a message with the given guardian set and return the signed VAA bytes
+  function signVaa(signers: MockGuardians, payload: Buffer): Buffer {
+    const published = suiEmitter.publishMessage(0, payload, 1);
+    return signers.addSignatures(published, [0]);
+  }
+
+  async function processVaa(vaaHash: Buffer, postedVaa: PublicKey, bridgeRequest: PublicKey) {
+    return program.methods
+      .processVaa(Array.from(vaaHash))
+      .accounts({ ...accounts, postedVaa, bridgeRequest })
+      .rpc();
+  }
+
+  // Version 2 envelope:
+  // [magic "OMNI"][version][op][source u16][source pool 32][target u16][nonce u64][body_len u16][body]
+  function envelope(version: number, op: number, targetChain: number, body: Buffer, sourcePool = suiPool): Buffer {
+    const header = Buffer.alloc(52);
+    header.write("OMNI", 0);
+    header.writeUInt8(version, 4);
+    header.writeUInt8(op, 5);
+    header.writeUInt16BE(CHAIN_ID_SUI, 6);
+    sourcePool.copy(header, 8);
+    header.writeUInt16BE(targetChain, 40);
+    header.writeBigUInt64BE(BigInt(42), 42);
+    header.writeUInt16BE(body.length, 50);
+    return Buffer.concat([header, body]);
+  }
+
+  // Sign, post and process `payload` from the registered Sui emitter
+  async function postAndProcess(payload: Buffer) {
+    const signedVaa = signVaa(guardians, payload);
+    await postVaaSolana(
+      provider.connection,
+      (tx) => provider.wallet.signTransaction(tx),
+      CORE_BRIDGE_PID,
+      payer.publicKey,
+      signedVaa
+    );
+    const { hash, sequence } = parseVaa(signedVaa);
+    const bridgeRequest = deriveBridgeRequest(suiEmitter, sequence);
+    await processVaa(hash, derivePostedVaaKey(CORE_BRIDGE_PID, hash), bridgeRequest);
+    return bridgeRequest;
+  }
+
+  // [op][pool_id][amount_a u64 BE][amount_b u64 BE][recipient], both amounts equal
+  function liquidityPayload(op: number, amount: number, recipient = payer.publicKey.toBuffer()): Buffer {
+    const amounts = Buffer.alloc(16);
+    amounts.writeBigUInt64BE(BigInt(amount), 0);
+    amounts.writeBigUInt64BE(BigInt(amount), 8);
+    return Buffer.concat([Buffer.from([op]), poolId, amounts, recipient]);
+  }
+  const addLiquidity = (amount: number) => liquidityPayload(1, amount);
+  const removeLiquidity = (amount: number) => liquidityPayload(2, amount);
+
+  // Deposit `amount` of both tokens into the pool from the payer
+  async function addLocalLiquidity(amount: number) {
+    const userTokenA = accounts.recipientTokenAAccount;
+    const userTokenB = accounts.recipientTokenBAccount;
+    await mintTo(provider.connection, payer, accounts.tokenAMint, userTokenA, payer.publicKey, BigInt(amount));
+    await mintTo(provider.connection, payer, accounts.tokenBMint, userTokenB, payer.publicKey, BigInt(amount));
+    await program.methods
+      .addLiquidity(new BN(amount), new BN(amount), new BN(0), new BN(0))
+      .accounts({
+        user: payer.publicKey,
+        pool: accounts.pool,
+        config: accounts.config,
+        poolAuthority: accounts.poolAuthority,
+        tokenAAccount: accounts.tokenAAccount,
+        tokenBAccount: accounts.tokenBAccount,
+        userTokenA,
+        userTokenB,
+        lpMint: accounts.lpMint,
+        userLpTokenAccount: accounts.recipientLpTokenAccount,
+        tokenAMint: accounts.tokenAMint,
+        tokenBMint: accounts.tokenBMint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  // Permissionless follow-ups on a bridge request parked by process_vaa
+  function release(bridgeRequest: PublicKey) {
+    return program.methods
+      .releaseBridgeRequest()
+      .accounts({
+        payer: payer.publicKey,
+        config: accounts.config,
+        bridgeRequest,
+        pool: accounts.pool,
+        poolAuthority: accounts.poolAuthority,
+        tokenAAccount: accounts.tokenAAccount,
+        tokenBAccount: accounts.tokenBAccount,
+        recipientTokenAAccount: accounts.recipientTokenAAccount,
+        recipientTokenBAccount: accounts.recipientTokenBAccount,
+        tokenAMint: accounts.tokenAMint,
+        tokenBMint: accounts.tokenBMint,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  function retry(bridgeRequest: PublicKey) {
+    const { wormholeProgram, foreignEmitter, rent, ...poolAccounts } = accounts;
+    return program.methods
+      .retryBridgeRequest()
+      .accounts({ ...poolAccounts, bridgeRequest })
+      .rpc();
+  }
+
+  // Send a failed deposit (or a deposit surplus) back to `refundAddress` on Sui; returns the posted message
+  async function refund(bridgeRequest: PublicKey, refundAddress: Buffer) {
+    const [wormholeEmitter] = PublicKey.findProgramAddressSync([Buffer.from("emitter"), accounts.pool.toBuffer()], program.programId);
+    const message = Keypair.generate();
+    await program.methods
+      .refundBridgeRequest(Array.from(refundAddress))
+      .accounts({
+        recipient: payer.publicKey,
+        config: accounts.config,
+        bridgeRequest,
+        pool: accounts.pool,
+        wormholeProgram: CORE_BRIDGE_PID,
+        wormholeBridge: PublicKey.findProgramAddressSync([Buffer.from("Bridge")], CORE_BRIDGE_PID)[0],
+        wormholeFeeCollector: PublicKey.findProgramAddressSync([Buffer.from("fee_collector")], CORE_BRIDGE_PID)[0],
+        wormholeEmitter,
+        wormholeSequence: PublicKey.findProgramAddressSync(
+          [Buffer.from("Sequence"), wormholeEmitter.toBuffer()],
+          CORE_BRIDGE_PID
+        )[0],
+        wormholeMessage: message.publicKey,
+        systemProgram: SystemProgram.programId,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([message])
+      .rpc();
+    return message;
+  }
+
+  // Replay-protection record for a VAA emitted by `emitter`
+  function deriveBridgeRequest(emitter: MockEmitter, sequence: bigint): PublicKey {
+    const sequenceBytes = Buffer.alloc(8);
+    sequenceBytes.writeBigUInt64LE(sequence);
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("bridge_request"),
+        new BN(emitter.chain).toArrayLike(Buffer, "le", 2),
+        Buffer.from(emitter.address, "hex"),
+        sequenceBytes,
+      ],
+      program.programId
+    )[0];
+  }
+
+  before(async () => {
+    // Initialize the core bridge with the local guardian set
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createInitializeInstruction(
+          CORE_BRIDGE_PID,
+          payer.publicKey,
+          86400,
+          BigInt(100),
+          guardians.getPublicKeys()
+        )
+      )
+    );
+
+    // A pool for the VAA to target (config is initialized by liquidity_pool.ts)
+    const [tokenAMint, tokenBMint] = sortMints(
+      await createMint(provider.connection, payer, payer.publicKey, null, 6),
+      await createMint(provider.connection, payer, payer.publicKey, null, 6)
+    );
+    const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), new BN(30).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [poolAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), pool.toBuffer()],
+      program.programId
+    );
+    const [tokenAAccount] = PublicKey.findProgramAddressSync([Buffer.from("token_a"), pool.toBuffer()], program.programId);
+    const [tokenBAccount] = PublicKey.findProgramAddressSync([Buffer.from("token_b"), pool.toBuffer()], program.programId);
+    const [lpMint] = PublicKey.findProgramAddressSync([Buffer.from("lp_mint"), pool.toBuffer()], program.programId);
+    const [lockedLpAccount] = PublicKey.findProgramAddressSync([Buffer.from("locked_lp"), pool.toBuffer()], program.programId);
+
+    await program.methods
+      .createPool(new BN(30), { constantProduct: {} }, new BN(0))
+      .accounts({
+        creator: payer.publicKey,
+        config,
+        pool,
+        poolAuthority,
+        tokenAMint,
+        tokenBMint,
+        lpMint,
+        lockedLpAccount,
+        tokenAAccount,
+        tokenBAccount,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    // Trust the mock Sui emitter
+    [suiForeignEmitter] = PublicKey.findProgramAddressSync(
+      [Buffer.from("foreign_emitter"), new BN(CHAIN_ID_SUI).toArrayLike(Buffer, "le", 2)],
+      program.programId
+    );
+    await program.methods
+      .registerEmitter(CHAIN_ID_SUI, Array.from(Buffer.from(suiEmitter.address, "hex")))
+      .accounts({
+        admin: payer.publicKey,
+        config,
+        foreignEmitter: suiForeignEmitter,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Link the pool to the Sui pool so bridge messages are accepted
+    const [wormholeEmitter] = PublicKey.findProgramAddressSync([Buffer.from("emitter"), pool.toBuffer()], program.programId);
+    const linkMessage = Keypair.generate();
+    await program.methods
+      .linkPool(CHAIN_ID_SUI, Array.from(suiPool))
+      .accounts({
+        admin: payer.publicKey,
+        config,
+        pool,
+        foreignEmitter: suiForeignEmitter,
+        wormholeProgram: CORE_BRIDGE_PID,
+        wormholeBridge: PublicKey.findProgramAddressSync([Buffer.from("Bridge")], CORE_BRIDGE_PID)[0],
+        wormholeFeeCollector: PublicKey.findProgramAddressSync([Buffer.from("fee_collector")], CORE_BRIDGE_PID)[0],
+        wormholeEmitter,
+        wormholeSequence: PublicKey.findProgramAddressSync(
+          [Buffer.from("Sequence"), wormholeEmitter.toBuffer()],
+          CORE_BRIDGE_PID
+        )[0],
+        wormholeMessage: linkMessage.publicKey,
+        systemProgram: SystemProgram.programId,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([linkMessage])
+      .rpc();
+
+    const linked = await program.account.pool.fetch(pool);
+    assert.equal(linked.linkedChainId, CHAIN_ID_SUI);
+    assert.isTrue(Buffer.from(linked.linkedAddress).equals(suiPool));
+    poolId = Buffer.from(linked.poolId);
+
+    const recipient = payer.publicKey;
+    const suiChain = new BN(CHAIN_ID_SUI).toArrayLike(Buffer, "le", 2);
+    const rateLimit = (scope: Buffer, mint: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("rate_limit"), scope, mint.toBuffer()], program.programId)[0];
+    accounts = {
+      payer: payer.publicKey,
+      wormholeProgram: CORE_BRIDGE_PID,
+      foreignEmitter: suiForeignEmitter,
+      pool,
+      config,
+      poolAuthority,
+      tokenAMint,
+      tokenBMint,
+      tokenAAccount,
+      tokenBAccount,
+      lpMint,
+      lockedLpAccount,
+      // Outflow caps, empty until set_*_rate_limit creates them
+      poolRateLimitA: rateLimit(pool.toBuffer(), tokenAMint),
+      poolRateLimitB: rateLimit(pool.toBuffer(), tokenBMint),
+      chainRateLimitA: rateLimit(suiChain, tokenAMint),
+      chainRateLimitB: rateLimit(suiChain, tokenBMint),
+      recipient,
+      recipientLpTokenAccount: getAssociatedTokenAddressSync(lpMint, recipient),
+      recipientTokenAAccount: getAssociatedTokenAddressSync(tokenAMint, recipient),
+      recipientTokenBAccount: getAssociatedTokenAddressSync(tokenBMint, recipient),
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    };
+  });
+
+  it("Rejects a fabricated VAA account not owned by the core bridge", async () => {
+    // Craft an account holding a plausible posted-VAA layout but owned by the system program
+    const fake = Keypair.generate();
+    const data = Buffer.concat([Buffer.from("vaa"), randomBytes(128)]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: fake.publicKey,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(data.length),
+          space: data.length,
+          programId: SystemProgram.programId,
+        })
+      ),
+      [fake]
+    );
+
+    try {
+      await processVaa(randomBytes(32), fake.publicKey, Keypair.generate().publicKey);
+      assert.fail("Fabricated VAA account should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "AccountOwnedByWrongProgram");
+    }
+  });
+
+  it("Rejects a VAA signed by keys outside the guardian set", async () => {
+    const impostors = new MockGuardians(0, [randomBytes(32).toString("hex")]);
+    const signedVaa = signVaa(impostors, Buffer.from([0]));
+
+    // The core bridge refuses to post it
+    let posted = true;
+    try {
+      await postVaaSolana(
+        provider.connection,
+        (tx) => provider.wallet.signTransaction(tx),
+        CORE_BRIDGE_PID,
+        payer.publicKey,
+        signedVaa
+      );
+    } catch (err) {
+      posted = false;
+    }
+    assert.isFalse(posted, "Core bridge should reject signatures from unknown guardians");
+
+    // So the PDA for its hash never exists and cannot be processed
+    const { hash, sequence } = parseVaa(signedVaa);
+    try {
+      await processVaa(hash, derivePostedVaaKey(CORE_BRIDGE_PID, hash), deriveBridgeRequest(suiEmitter, sequence));
+      assert.fail("Unsigned VAA should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("Rejects a verified VAA passed with a different vaa_hash", async () => {
+    const signedVaa = signVaa(guardians, Buffer.from([0]));
+    await postVaaSolana(
+      provider.connection,
+      (tx) => provider.wallet.signTransaction(tx),
+      CORE_BRIDGE_PID,
+      payer.publicKey,
+      signedVaa
+    );
+    const { hash, sequence } = parseVaa(signedVaa);
+    const postedVaa = derivePostedVaaKey(CORE_BRIDGE_PID, hash);
+
+    try {
+      await processVaa(randomBytes(32), postedVaa, deriveBridgeRequest(suiEmitter, sequence));
+      assert.fail("VAA account must be bound to the vaa_hash argument");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintSeeds");
+    }
+  });
+
+  it("Rejects a verified VAA from an unregistered emitter", async () => {
+    const rogueEmitter = new MockEmitter(randomBytes(32).toString("hex"), CHAIN_ID_SUI);
+    const signedVaa = guardians.addSignatures(rogueEmitter.publishMessage(0, Buffer.from([0]), 1), [0]);
+    await postVaaSolana(
+      provider.connection,
+      (tx) => provider.wallet.signTransaction(tx),
+      CORE_BRIDGE_PID,
+      payer.publicKey,
+      signedVaa
+    );
+    const { hash, sequence } = parseVaa(signedVaa);
+
+    try {
+      await processVaa(hash, derivePostedVaaKey(CORE_BRIDGE_PID, hash), deriveBridgeRequest(rogueEmitter, sequence));
+      assert.fail("VAA from an unregistered emitter should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidEmitterAddress");
+    }
+  });
+
+  it("Rejects a VAA that was already processed", async () => {
+    // ADD_LIQUIDITY from Sui: [op=1][pool_id][amount_a u64 BE][amount_b u64 BE][recipient]
+    const amounts = Buffer.alloc(16);
+    amounts.writeBigUInt64BE(BigInt(2_000), 0);
+    amounts.writeBigUInt64BE(BigInt(2_000), 8);
+    const payload = Buffer.concat([Buffer.from([1]), poolId, amounts, payer.publicKey.toBuffer()]);
+
+    const signedVaa = signVaa(guardians, payload);
+    await postVaaSolana(
+      provider.connection,
+      (tx) => provider.wallet.signTransaction(tx),
+      CORE_BRIDGE_PID,
+      payer.publicKey,
+      signedVaa
+    );
+    const parsed = parseVaa(signedVaa);
+    const postedVaa = derivePostedVaaKey(CORE_BRIDGE_PID, parsed.hash);
+    const bridgeRequest = deriveBridgeRequest(suiEmitter, parsed.sequence);
+
+    await processVaa(parsed.hash, postedVaa, bridgeRequest);
+    const record = await program.account.bridgeRequest.fetch(bridgeRequest);
+    assert.deepEqual(record.status, { completed: {} });
+    assert.equal(record.emitterChain, CHAIN_ID_SUI);
+    assert.equal(record.wormholeSequence.toString(), parsed.sequence.toString());
+
+    try {
+      await processVaa(parsed.hash, postedVaa, bridgeRequest);
+      assert.fail("Replayed VAA should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "VaaAlreadyProcessed");
+    }
+  });
+
+  it("Processes a version 2 enveloped payload", async () => {
+    // ADD_LIQUIDITY body: [pool_id][amount_a u64 BE][amount_b u64 BE][recipient]
+    const amounts = Buffer.alloc(16);
+    amounts.writeBigUInt64BE(BigInt(500), 0);
+    amounts.writeBigUInt64BE(BigInt(500), 8);
+    const bridgeRequest = await postAndProcess(
+      envelope(2, 1, 1, Buffer.concat([poolId, amounts, payer.publicKey.toBuffer()]))
+    );
+    const record = await program.account.bridgeRequest.fetch(bridgeRequest);
+    assert.deepEqual(record.status, { completed: {} });
+  });
+
+  it("Books remote deposits and withdrawals in the remote reserves", async () => {
+    const before = await program.account.pool.fetch(accounts.pool);
+    // Both ADD_LIQUIDITY tests above deposited on Sui, nothing reached the vaults
+    assert.equal(before.remoteReserveA.toString(), "2500");
+    assert.equal(before.remoteReserveB.toString(), "2500");
+    const supply = (await getMint(provider.connection, accounts.lpMint)).supply;
+    assert.equal(before.totalLiquidity.toString(), supply.toString());
+
+    // Burning a fifth of the LP (1000 of it locked) asks Sui for a fifth of what it holds for us
+    const [wormholeEmitter] = PublicKey.findProgramAddressSync([Buffer.from("emitter"), accounts.pool.toBuffer()], program.programId);
+    const message = Keypair.generate();
+    const recipientOnSui = randomBytes(32);
+    await program.methods
+      .requestRemoveLiquidityRemote(new BN(500), new BN(0), new BN(0), Array.from(recipientOnSui))
+      .accounts({
+        user: payer.publicKey,
+        pool: accounts.pool,
+        lpMint: accounts.lpMint,
+        userLpTokenAccount: accounts.recipientLpTokenAccount,
+        wormholeProgram: CORE_BRIDGE_PID,
+        wormholeBridge: PublicKey.findProgramAddressSync([Buffer.from("Bridge")], CORE_BRIDGE_PID)[0],
+        wormholeFeeCollector: PublicKey.findProgramAddressSync([Buffer.from("fee_collector")], CORE_BRIDGE_PID)[0],
+        wormholeEmitter,
+        wormholeSequence: PublicKey.findProgramAddressSync(
+          [Buffer.from("Sequence"), wormholeEmitter.toBuffer()],
+          CORE_BRIDGE_PID
+        )[0],
+        wormholeMessage: message.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([message])
+      .rpc();
+
+    const after = await program.account.pool.fetch(accounts.pool);
+    assert.equal(after.remoteReserveA.toString(), "2000");
+    assert.equal(after.remoteReserveB.toString(), "2000");
+    assert.equal(after.totalLiquidity.toString(), "2000");
+
+    // REMOVE_LIQUIDITY, 81 bytes: [op=2][pool_id][amount_a u64 BE][amount_b u64 BE][recipient]
+    const { message: posted } = await getPostedMessage(provider.connection, message.publicKey);
+    assert.equal(
+      posted.payload.toString("hex"),
+      "02" + poolId.toString("hex") + "00000000000001f4" + "00000000000001f4" + recipientOnSui.toString("hex")
+    );
+  });
+
+  it("Refuses to relink a pool with reserves on the linked chain", async () => {
+    const before = await program.account.pool.fetch(accounts.pool);
+    assert.isTrue(before.remoteReserveA.gtn(0));
+
+    const [wormholeEmitter] = PublicKey.findProgramAddressSync([Buffer.from("emitter"), accounts.pool.toBuffer()], program.programId);
+    const message = Keypair.generate();
+    try {
+      await program.methods
+        .linkPool(CHAIN_ID_SUI, Array.from(randomBytes(32)))
+        .accounts({
+          admin: payer.publicKey,
+          config: accounts.config,
+          pool: accounts.pool,
+          foreignEmitter: suiForeignEmitter,
+          wormholeProgram: CORE_BRIDGE_PID,
+          wormholeBridge: PublicKey.findProgramAddressSync([Buffer.from("Bridge")], CORE_BRIDGE_PID)[0],
+          wormholeFeeCollector: PublicKey.findProgramAddressSync([Buffer.from("fee_collector")], CORE_BRIDGE_PID)[0],
+          wormholeEmitter,
+          wormholeSequence: PublicKey.findProgramAddressSync(
+            [Buffer.from("Sequence"), wormholeEmitter.toBuffer()],
+            CORE_BRIDGE_PID
+          )[0],
+          wormholeMessage: message.publicKey,
+          systemProgram: SystemProgram.programId,
+          clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([message])
+        .rpc();
+      assert.fail("Relinking with remote reserves should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "RemoteReservesOutstanding");
+    }
+
+    const after = await program.account.pool.fetch(accounts.pool);
+    assert.isTrue(Buffer.from(after.linkedAddress).equals(suiPool));
+  });
+
+  it("Rejects an envelope with an unknown version", async () => {
+    try {
+      await postAndProcess(envelope(9, 1, 1, Buffer.alloc(80)));
+      assert.fail("Unknown payload version should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "UnsupportedPayloadVersion");
+    }
+  });
+
+  it("Rejects an envelope addressed to another chain", async () => {
+    const body = Buffer.concat([poolId, Buffer.alloc(16), payer.publicKey.toBuffer()]);
+    try {
+      await postAndProcess(envelope(2, 1, CHAIN_ID_SUI, body));
+      assert.fail("Envelope for another chain should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidTargetChain");
+    }
+  });
+
+  it("Creates a linked pool from a CREATE_POOL VAA", async () => {
+    const [tokenAMint, tokenBMint] = sortMints(
+      await createMint(provider.connection, payer, payer.publicKey, null, 6),
+      await createMint(provider.connection, payer, payer.publicKey, null, 6)
+    );
+    const suiPool = randomBytes(32);
+    const fee = Buffer.alloc(8);
+    fee.writeBigUInt64BE(BigInt(30));
+    // The factory derives the pool ID like create_pool does
+    const remotePoolId = keccak256(Buffer.concat([Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), fee]));
+
+    // [op=4][source pool][pool_id][token_a_mint][token_b_mint][fee u64 BE]
+    const payload = Buffer.concat([
+      Buffer.from([4]),
+      suiPool,
+      remotePoolId,
+      tokenAMint.toBuffer(),
+      tokenBMint.toBuffer(),
+      fee,
+    ]);
+    const signedVaa = signVaa(guardians, payload);
+    await postVaaSolana(
+      provider.connection,
+      (tx) => provider.wallet.signTransaction(tx),
+      CORE_BRIDGE_PID,
+      payer.publicKey,
+      signedVaa
+    );
+    const { hash, sequence } = parseVaa(signedVaa);
+
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), new BN(30).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const pda = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), pool.toBuffer()], program.programId)[0];
+
+    await program.methods
+      .createPoolFromVaa(Array.from(hash), new BN(30))
+      .accounts({
+        payer: payer.publicKey,
+        config: accounts.config,
+        wormholeProgram: CORE_BRIDGE_PID,
+        postedVaa: derivePostedVaaKey(CORE_BRIDGE_PID, hash),
+        foreignEmitter: suiForeignEmitter,
+        bridgeRequest: deriveBridgeRequest(suiEmitter, sequence),
+        pool,
+        poolAuthority: pda("authority"),
+        tokenAMint,
+        tokenBMint,
+        lpMint: pda("lp_mint"),
+        tokenAAccount: pda("token_a"),
+        tokenBAccount: pda("token_b"),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const created = await program.account.pool.fetch(pool);
+    assert.isTrue(Buffer.from(created.poolId).equals(remotePoolId));
+    assert.equal(created.feePercentage.toNumber(), 30);
+    assert.equal(created.linkedChainId, CHAIN_ID_SUI);
+    assert.isTrue(Buffer.from(created.linkedAddress).equals(suiPool));
+  });
+
+  it("Rejects an envelope sent by a pool other than the linked one", async () => {
+    const body = Buffer.concat([poolId, Buffer.alloc(16), payer.publicKey.toBuffer()]);
+    try {
+      await postAndProcess(envelope(2, 1, 1, body, randomBytes(32)));
+      assert.fail("Envelope from an unlinked pool should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "PoolLinkMismatch");
+    }
+  });
+
+  it("Rejects liquidity messages for another pool", async () => {
+    // Bare Sui payloads name no sender, the pool_id is what ties them to our pool
+    const amounts = Buffer.alloc(16);
+    amounts.writeBigUInt64BE(BigInt(1_000), 0);
+    amounts.writeBigUInt64BE(BigInt(1_000), 8);
+    for (const op of [1, 2]) {
+      try {
+        await postAndProcess(Buffer.concat([Buffer.from([op]), randomBytes(32), amounts, payer.publicKey.toBuffer()]));
+        assert.fail("Message for another pool should be rejected");
+      } catch (err) {
+        assert.include(err.toString(), "PoolIdMismatch");
+      }
+    }
+  });
+
+  it("Accepts LINK_POOL only from the linked pool", async () => {
+    await postAndProcess(Buffer.concat([Buffer.from([3]), suiPool]));
+
+    try {
+      await postAndProcess(Buffer.concat([Buffer.from([3]), randomBytes(32)]));
+      assert.fail("LINK_POOL for another pool should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "PoolLinkMismatch");
+    }
+  });
+
+  it("Delays withdrawals over the rate limit until released or cancelled", async () => {
+    // Local liquidity for the withdrawals to be paid from
+    await addLocalLiquidity(100_000);
+
+    const setLimit = (releaseDelay: number) =>
+      program.methods
+        .setPoolRateLimit(new BN(1_000), new BN(86_400), new BN(releaseDelay))
+        .accounts({
+          admin: payer.publicKey,
+          config: accounts.config,
+          pool: accounts.pool,
+          mint: accounts.tokenAMint,
+          rateLimit: accounts.poolRateLimitA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const withdrawal = removeLiquidity(5_000);
+
+    // 5000 A over a 1000 A cap with a one hour cooldown
+    await setLimit(3_600);
+    const vaultBefore = (await getAccount(provider.connection, accounts.tokenAAccount)).amount;
+    const delayed = await postAndProcess(withdrawal);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(delayed)).status, { delayed: {} });
+    assert.equal((await getAccount(provider.connection, accounts.tokenAAccount)).amount, vaultBefore);
+
+    try {
+      await release(delayed);
+      assert.fail("Release before the cooldown should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "BridgeRequestLocked");
+    }
+
+    await program.methods
+      .cancelBridgeRequest()
+      .accounts({ admin: payer.publicKey, config: accounts.config, bridgeRequest: delayed })
+      .rpc();
+    assert.deepEqual((await program.account.bridgeRequest.fetch(delayed)).status, { cancelled: {} });
+
+    // Without a cooldown the delayed withdrawal can be released right away
+    await setLimit(0);
+    const releasable = await postAndProcess(withdrawal);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(releasable)).status, { delayed: {} });
+    const balanceBefore = (await getAccount(provider.connection, accounts.recipientTokenAAccount)).amount;
+    await release(releasable);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(releasable)).status, { completed: {} });
+    assert.equal((await getAccount(provider.connection, accounts.recipientTokenAAccount)).amount - balanceBefore, 5_000n);
+  });
+
+  it("Records a withdrawal the pool can't cover as Failed and retries it", async () => {
+    const failed = await postAndProcess(removeLiquidity(1_000_000));
+    const record = await program.account.bridgeRequest.fetch(failed);
+    assert.deepEqual(record.status, { failed: {} });
+    assert.deepEqual(record.failureReason, { insufficientReserves: {} });
+
+    try {
+      await retry(failed);
+      assert.fail("Retry without the reserves should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientPoolReserves");
+    }
+
+    // Once the reserves are there the retry goes through the rate limit set above (no cooldown)
+    await addLocalLiquidity(1_000_000);
+    await retry(failed);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(failed)).status, { delayed: {} });
+    await release(failed);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(failed)).status, { completed: {} });
+  });
+
+  it("Refunds a deposit into a paused pool to the source chain", async () => {
+    const setStatus = (status: object) =>
+      program.methods
+        .setPoolStatus(status as any)
+        .accounts({ admin: payer.publicKey, config: accounts.config, pool: accounts.pool })
+        .rpc();
+
+    await setStatus({ paused: {} });
+    const failed = await postAndProcess(addLiquidity(7_000));
+    const record = await program.account.bridgeRequest.fetch(failed);
+    assert.deepEqual(record.status, { failed: {} });
+    assert.deepEqual(record.failureReason, { poolNotActive: {} });
+
+    try {
+      await retry(failed);
+      assert.fail("Retry into a paused pool should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "PoolPaused");
+    }
+
+    const refundAddress = randomBytes(32);
+    const message = await refund(failed, refundAddress);
+    assert.deepEqual((await program.account.bridgeRequest.fetch(failed)).status, { refunded: {} });
+
+    // REMOVE_LIQUIDITY for the deposited amounts, paid to the refund address on Sui
+    const { message: posted } = await getPostedMessage(provider.connection, message.publicKey);
+    assert.equal(
+      posted.payload.toString("hex"),
+      "02" + poolId.toString("hex") + "0000000000001b58" + "0000000000001b58" + refundAddress.toString("hex")
+    );
+
+    await setStatus({ active: {} });
+  });
+
+  it("Books only the pool ratio of an imbalanced remote deposit and refunds the surplus", async () => {
+    const before = await program.account.pool.fetch(accounts.pool);
+    const reserveA = before.reserveA.add(before.remoteReserveA);
+    const reserveB = before.reserveB.add(before.remoteReserveB);
+
+    // Three times as much B as A: the deposit is taken at the pool ratio on A, the rest of B stays on Sui
+    const amountA = new BN(3_000);
+    const amountB = new BN(9_000);
+    const bookedB = amountA.mul(reserveB).div(reserveA);
+    assert.ok(bookedB.lt(amountB));
+    const amounts = Buffer.alloc(16);
+    amounts.writeBigUInt64BE(BigInt(amountA.toString()), 0);
+    amounts.writeBigUInt64BE(BigInt(amountB.toString()), 8);
+    const deposit = await postAndProcess(Buffer.concat([Buffer.from([1]), poolId, amounts, payer.publicKey.toBuffer()]));
+
+    const after = await program.account.pool.fetch(accounts.pool);
+    assert.ok(after.remoteReserveA.eq(before.remoteReserveA.add(amountA)));
+    assert.ok(after.remoteReserveB.eq(before.remoteReserveB.add(bookedB)));
+
+    const record = await program.account.bridgeRequest.fetch(deposit);
+    assert.deepEqual(record.status, { completed: {} });
+    assert.equal(record.surplusA.toString(), "0");
+    assert.ok(record.surplusB.eq(amountB.sub(bookedB)));
+
+    // The surplus goes back to Sui, once
+    const refundAddress = randomBytes(32);
+    const message = await refund(deposit, refundAddress);
+    const { message: posted } = await getPostedMessage(provider.connection, message.publicKey);
+    assert.equal(
+      posted.payload.toString("hex"),
+      "02" + poolId.toString("hex") + "0000000000000000" + amountB.sub(bookedB).toString("hex", 16) + refundAddress.toString("hex")
+    );
+    const settled = await program.account.bridgeRequest.fetch(deposit);
+    assert.deepEqual(settled.status, { completed: {} });
+    assert.equal(settled.surplusB.toString(), "0");
+
+    try {
+      await refund(deposit, refundAddress);
+      assert.fail("A settled surplus should not be refunded again");
+    } catch (err) {
+      assert.include(err.toString(), "BridgeRequestNotRefundable");
+    }
+  });
+});
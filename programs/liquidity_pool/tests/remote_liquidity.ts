@@ -0,0 +1,195 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { LiquidityPoolProgram } from "../target/types/liquidity_pool_program";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { CHAIN_ID_SUI } from "@certusone/wormhole-sdk";
+import { getPostedMessage } from "@certusone/wormhole-sdk/lib/cjs/solana/wormhole";
+import { assert } from "chai";
+import { randomBytes } from "crypto";
+
+// Runs after process_vaa.ts, which initializes the core bridge loaded at genesis
+const CORE_BRIDGE_PID = new PublicKey("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+// Pools take their mints in canonical (byte-wise ascending) order
+function sortMints(a: PublicKey, b: PublicKey): [PublicKey, PublicKey] {
+  return Buffer.compare(a.toBuffer(), b.toBuffer()) < 0 ? [a, b] : [b, a];
+}
+
+describe("outbound bridge messages", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .LiquidityPoolProgram as Program<LiquidityPoolProgram>;
+  const payer = provider.wallet.payer;
+
+  let poolAccounts: Record<string, PublicKey>;
+  let wormholeAccounts: Record<string, PublicKey>;
+  let userLpTokenAccount: PublicKey;
+
+  before(async () => {
+    const [tokenAMint, tokenBMint] = sortMints(
+      await createMint(provider.connection, payer, payer.publicKey, null, 6),
+      await createMint(provider.connection, payer, payer.publicKey, null, 6)
+    );
+    const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), new BN(30).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [poolAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), pool.toBuffer()],
+      program.programId
+    );
+    const [tokenAAccount] = PublicKey.findProgramAddressSync([Buffer.from("token_a"), pool.toBuffer()], program.programId);
+    const [tokenBAccount] = PublicKey.findProgramAddressSync([Buffer.from("token_b"), pool.toBuffer()], program.programId);
+    const [lpMint] = PublicKey.findProgramAddressSync([Buffer.from("lp_mint"), pool.toBuffer()], program.programId);
+
+    await program.methods
+      .createPool(new BN(30), { constantProduct: {} }, new BN(0))
+      .accounts({
+        creator: payer.publicKey,
+        config,
+        pool,
+        poolAuthority,
+        tokenAMint,
+        tokenBMint,
+        lpMint,
+        tokenAAccount,
+        tokenBAccount,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const userTokenA = await createAccount(provider.connection, payer, tokenAMint, payer.publicKey);
+    const userTokenB = await createAccount(provider.connection, payer, tokenBMint, payer.publicKey);
+    await mintTo(provider.connection, payer, tokenAMint, userTokenA, payer.publicKey, 1_000_000_000n);
+    await mintTo(provider.connection, payer, tokenBMint, userTokenB, payer.publicKey, 1_000_000_000n);
+    userLpTokenAccount = getAssociatedTokenAddressSync(lpMint, payer.publicKey);
+
+    poolAccounts = {
+      user: payer.publicKey,
+      pool,
+      config,
+      poolAuthority,
+      tokenAMint,
+      tokenBMint,
+      tokenAAccount,
+      tokenBAccount,
+      userTokenA,
+      userTokenB,
+      userWsolAccount: null,
+      lpMint,
+      userLpTokenAccount,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    };
+
+    const [wormholeEmitter] = PublicKey.findProgramAddressSync([Buffer.from("emitter"), pool.toBuffer()], program.programId);
+    wormholeAccounts = {
+      wormholeProgram: CORE_BRIDGE_PID,
+      wormholeBridge: PublicKey.findProgramAddressSync([Buffer.from("Bridge")], CORE_BRIDGE_PID)[0],
+      wormholeFeeCollector: PublicKey.findProgramAddressSync([Buffer.from("fee_collector")], CORE_BRIDGE_PID)[0],
+      wormholeEmitter,
+      wormholeSequence: PublicKey.findProgramAddressSync(
+        [Buffer.from("Sequence"), wormholeEmitter.toBuffer()],
+        CORE_BRIDGE_PID
+      )[0],
+      clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+    };
+
+    // Bridge operations need a linked pool; the Sui emitter is registered by process_vaa.ts
+    const linkMessage = Keypair.generate();
+    await program.methods
+      .linkPool(CHAIN_ID_SUI, Array.from(randomBytes(32)))
+      .accounts({
+        admin: payer.publicKey,
+        config,
+        pool,
+        foreignEmitter: PublicKey.findProgramAddressSync(
+          [Buffer.from("foreign_emitter"), new BN(CHAIN_ID_SUI).toArrayLike(Buffer, "le", 2)],
+          program.programId
+        )[0],
+        ...wormholeAccounts,
+        wormholeMessage: linkMessage.publicKey,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([linkMessage])
+      .rpc();
+  });
+
+  it("Adds liquidity and publishes the deposit", async () => {
+    const message = Keypair.generate();
+    await program.methods
+      .bridgeAddLiquidity(new BN(1_000_000), new BN(4_000_000), new BN(0), new BN(0))
+      .accounts({ ...poolAccounts, ...wormholeAccounts, wormholeMessage: message.publicKey })
+      .signers([message])
+      .rpc();
+
+    const lpBalance = (await getAccount(provider.connection, userLpTokenAccount)).amount;
+    // sqrt(1e6 * 4e6) = 2e6, less the 1000 locked on the first deposit
+    assert.equal(lpBalance.toString(), "1999000");
+
+    // Exactly the 49-byte ADD_LIQUIDITY layout Sui's process_vaa_for_pool accepts:
+    // [op=1][pool_id][amount_a u64 BE][amount_b u64 BE]
+    const { poolId } = await program.account.pool.fetch(poolAccounts.pool);
+    const { message: posted } = await getPostedMessage(provider.connection, message.publicKey);
+    assert.equal(posted.emitterAddress.toString("hex"), wormholeAccounts.wormholeEmitter.toBuffer().toString("hex"));
+    assert.equal(
+      posted.payload.toString("hex"),
+      "01" + Buffer.from(poolId).toString("hex") + "00000000000f4240" + "00000000003d0900"
+    );
+  });
+
+  it("Rejects a remote removal larger than the remote reserves", async () => {
+    // Everything in this pool was deposited on Solana, Sui holds nothing for its LPs.
+    // process_vaa.ts covers a removal backed by remote deposits.
+    const message = Keypair.generate();
+    const { userTokenA, userTokenB, userWsolAccount, poolAuthority, config, associatedTokenProgram, ...accounts } = poolAccounts;
+
+    try {
+      await program.methods
+        .requestRemoveLiquidityRemote(new BN(250_000), new BN(0), new BN(0), Array.from(randomBytes(32)))
+        .accounts({ ...accounts, ...wormholeAccounts, wormholeMessage: message.publicKey })
+        .signers([message])
+        .rpc();
+      assert.fail("Removal exceeding the remote reserves should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientPoolReserves");
+    }
+  });
+
+  it("Rejects a remote removal without a recipient", async () => {
+    const message = Keypair.generate();
+    const { userTokenA, userTokenB, userWsolAccount, poolAuthority, config, associatedTokenProgram, ...accounts } = poolAccounts;
+
+    try {
+      await program.methods
+        .requestRemoveLiquidityRemote(new BN(1_000), new BN(0), new BN(0), Array.from(Buffer.alloc(32)))
+        .accounts({ ...accounts, ...wormholeAccounts, wormholeMessage: message.publicKey })
+        .signers([message])
+        .rpc();
+      assert.fail("Empty recipient should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidRemoteRecipient");
+    }
+  });
+});
@@ -35,6 +35,7 @@ module omnisphere_sui::bridge_interface {
     const EVAAConsumedOrInvalid: u64 = 104;
     const EAddressConversionError: u64 = 105; // Error for address conversion
     const EFactoryMismatch: u64 = 106; // Error if VAA emitter is not the expected factory
+    const EPoolIdMismatch: u64 = 107; // Error if the payload names another pool
 
     // === Helper Functions ===
 
@@ -89,7 +90,8 @@ module omnisphere_sui::bridge_interface {
         assert!(option::is_some(&parsed_vaa_option), EVAAConsumedOrInvalid);
         let vaa = option::destroy_some(parsed_vaa_option);
 
-        // 2. Check Emitter matches the linked address and chain ID stored in the pool
+        // 2. Check Emitter matches the linked address and chain ID stored in the pool.
+        // Solana pools emit from their own emitter PDA, so this identifies the sending pool.
         let (linked_chain, linked_addr_bytes) = liquidity_pool::get_link_info(pool);
         // Convert linked_addr_bytes to address for comparison if necessary, or compare bytes directly
         // Assuming vaa.emitter_address is vector<u8>
@@ -105,12 +107,18 @@ module omnisphere_sui::bridge_interface {
 
         let operation_type = *vector::borrow(&payload, 0);
 
+        // Liquidity payloads name the pair by the Solana pool's `pool_id` right after the op code
+        if (operation_type == OPERATION_ADD_LIQUIDITY || operation_type == OPERATION_REMOVE_LIQUIDITY) {
+            assert!(payload_len >= 33, EInvalidVAAPayload);
+            assert!(vector::slice(&payload, 1, 33) == liquidity_pool::get_linked_pool_id(pool), EPoolIdMismatch);
+        };
+
         // 5. Dispatch based on Operation Type defined in `types.move`
         if (operation_type == OPERATION_ADD_LIQUIDITY) {
-            // Expected Payload: [op_code(1)] [amount_a(8)] [amount_b(8)] = 17 bytes total
-            assert!(payload_len == 17, EInvalidVAAPayload);
-            let amount_a = bytes_to_u64(&payload, 1); // Read amount_a from index 1
-            let amount_b = bytes_to_u64(&payload, 9); // Read amount_b from index 9
+            // Expected Payload: [op_code(1)] [pool_id(32)] [amount_a(8)] [amount_b(8)] = 49 bytes total
+            assert!(payload_len == 49, EInvalidVAAPayload);
+            let amount_a = bytes_to_u64(&payload, 33); // Read amount_a from index 33
+            let amount_b = bytes_to_u64(&payload, 41); // Read amount_b from index 41
 
             // Call the corresponding function in the liquidity pool module
             // This function needs `public(friend)` visibility in liquidity_pool.move
@@ -127,11 +135,11 @@ module omnisphere_sui::bridge_interface {
             emit_vaa_processed(pool_id, operation_type, &vaa, ctx);
 
         } else if (operation_type == OPERATION_REMOVE_LIQUIDITY) {
-            // Expected Payload: [op_code(1)] [amount_a(8)] [amount_b(8)] [recipient_on_sui(32)] = 49 bytes total
-            assert!(payload_len == 49, EInvalidVAAPayload);
-            let amount_a = bytes_to_u64(&payload, 1);       // Read amount_a from index 1
-            let amount_b = bytes_to_u64(&payload, 9);       // Read amount_b from index 9
-            let recipient_on_sui = bytes_to_address(&payload, 17); // Read recipient Sui address from index 17
+            // Expected Payload: [op_code(1)] [pool_id(32)] [amount_a(8)] [amount_b(8)] [recipient_on_sui(32)] = 81 bytes total
+            assert!(payload_len == 81, EInvalidVAAPayload);
+            let amount_a = bytes_to_u64(&payload, 33);      // Read amount_a from index 33
+            let amount_b = bytes_to_u64(&payload, 41);      // Read amount_b from index 41
+            let recipient_on_sui = bytes_to_address(&payload, 49); // Read recipient Sui address from index 49
 
             // Call the corresponding function in the liquidity pool module
             // This function needs `public(friend)` visibility and returns the coins
@@ -201,10 +209,12 @@ module omnisphere_sui::bridge_interface {
         // 5. Ensure operation is CREATE_POOL
         assert!(operation_type == OPERATION_CREATE_POOL, EInvalidOperationType);
 
-        // TODO: Extract necessary parameters for pool creation from the payload.
-        // Assuming Payload: [op_code(1)] [source_link_address(32)] = 33 bytes
-        assert!(payload_len == 33, EInvalidVAAPayload);
+        // Payload: [op_code(1)] [source_link_address(32)] followed by the pool parameters
+        // [pool_id(32)] [token_a(32)] [token_b(32)] [fee_percentage(8)] = 137 bytes.
+        // Only the pool ID is needed here, liquidity messages for the new pool carry it.
+        assert!(payload_len == 137, EInvalidVAAPayload);
         let source_link_address = vector::slice(&payload, 1, 33);
+        let source_pool_id = vector::slice(&payload, 33, 65);
 
         // 6. Call Factory function to create and link the pool
         let new_pool = factory::create_pool_from_vaa<CoinTypeA, CoinTypeB>(
@@ -214,6 +224,7 @@ module omnisphere_sui::bridge_interface {
             vaa.emitter_chain_id,      // Source chain from VAA
             vaa.emitter_address,       // Source factory address from VAA
             source_link_address,       // Source pool address from Payload
+            source_pool_id,            // Source pool ID from Payload
             wormhole_state,            // Wormhole state for linking
             link_message_fee,          // Fee for linking message
             ctx
@@ -129,6 +129,7 @@ module omnisphere_sui::factory {
         // We need the address of the source *pool* or *entity* to link back to.
         // This must be part of the VAA payload sent by the source factory.
         source_link_address: vector<u8>, // e.g., the ID of the pool on the source chain
+        source_pool_id: vector<u8>,      // `pool_id` of the source pool, carried by liquidity messages
         // Parameters needed for linking back:
         wormhole_state: &WormholeState,
         link_message_fee: Coin<SUI>,
@@ -155,6 +156,7 @@ module omnisphere_sui::factory {
             &mut new_pool,            // Pass the newly created pool
             source_chain_id,        // Link back to the source chain
             source_link_address,    // Link back to the specific address from payload
+            source_pool_id,         // Pool ID from payload
             wormhole_state,         // Wormhole state object
             link_message_fee,       // Fee for the link message
             ctx
@@ -203,6 +205,7 @@ module omnisphere_sui::factory {
             status: types::new_active_status(),
             linked_chain_id: 0,
             linked_address: vector::empty<u8>(),
+            linked_pool_id: vector::empty<u8>(),
         }
         // NOTE: This placeholder is highly simplified and needs proper implementation
         // using TreasuryCaps passed into create_pool_from_vaa and potentially modifying
@@ -21,7 +21,7 @@ module omnisphere_sui::liquidity_pool {
 
     // === Other Modules ===
     use omnisphere_sui::types::{Self, PoolStatus, new_active_status, is_active as pool_is_active,
-                               OPERATION_ADD_LIQUIDITY, OPERATION_REMOVE_LIQUIDITY};
+                               OPERATION_ADD_LIQUIDITY, OPERATION_REMOVE_LIQUIDITY, OPERATION_LINK_POOL};
     use omnisphere_sui::events::{
         emit_pool_created, emit_liquidity_added, emit_pool_linked,
         emit_liquidity_removal_requested
@@ -34,6 +34,7 @@ module omnisphere_sui::liquidity_pool {
     const EInsufficientLiquidity: u64 = 5;
     const ETransferFailed: u64 = 6; // Transfer hatası için
     const EArithmeticError: u64 = 7; // Aritmetik taşma vb.
+    const EInvalidPoolId: u64 = 8;
 
     // --- Structs ---
     struct Pool<phantom CoinTypeA, phantom CoinTypeB> has key, store {
@@ -44,6 +45,8 @@ module omnisphere_sui::liquidity_pool {
         status: PoolStatus,
         linked_chain_id: u16,
         linked_address: vector<u8>,
+        // `pool_id` of the Solana side of the pair; every liquidity message carries it
+        linked_pool_id: vector<u8>,
     }
 
     // --- Helper Functions ---
@@ -127,6 +130,7 @@ module omnisphere_sui::liquidity_pool {
             status: new_active_status(),
             linked_chain_id: 0,          // Not linked initially
             linked_address: vector::empty<u8>(), // Not linked initially
+            linked_pool_id: vector::empty<u8>(), // Not linked initially
         };
 
         events::emit_pool_created(
@@ -272,9 +276,10 @@ module omnisphere_sui::liquidity_pool {
         // This prevents locking funds before the cross-chain message is confirmed.
 
         // Construct Payload for Wormhole message:
-        // [op_code(1)] [amount_a(8)] [amount_b(8)] [recipient(variable)]
+        // [op_code(1)] [linked_pool_id(32)] [amount_a(8)] [amount_b(8)] [recipient(variable)]
         let mut payload = vector::empty<u8>();
         vector::push_back(&mut payload, OPERATION_REMOVE_LIQUIDITY);
+        vector::append(&mut payload, pool.linked_pool_id);
         vector::append(&mut payload, u64_to_bytes(amount_a_to_remove));
         vector::append(&mut payload, u64_to_bytes(amount_b_to_remove));
         vector::append(&mut payload, recipient_on_remote); // Append recipient address
@@ -305,10 +310,14 @@ module omnisphere_sui::liquidity_pool {
     }
 
     /// Links the pool to a remote pool and publishes a Wormhole message. (REAL IMPLEMENTATION)
+    /// `remote_address` is the address the remote pool emits from: for a Solana pool that is its
+    /// emitter PDA (`["emitter", pool]`), which its LINK_POOL message carries.
+    /// `remote_pool_id` is the Solana pool's `pool_id`, checked against every liquidity message.
     public fun link_and_publish<CoinTypeA, CoinTypeB>(
         pool: &mut Pool<CoinTypeA, CoinTypeB>,
         remote_chain_id: u16,
         remote_address: vector<u8>,
+        remote_pool_id: vector<u8>,
         wormhole_state: &WormholeState, // Input: Wormhole State object
         message_fee: Coin<SUI>,      // Input: Fee coin
         ctx: &mut TxContext
@@ -316,6 +325,7 @@ module omnisphere_sui::liquidity_pool {
         // 1. Auth Check
         let sender = tx_context::sender(ctx);
         assert!(sender == pool.admin, ENotAdmin);
+        assert!(vector::length(&remote_pool_id) == 32, EInvalidPoolId);
 
         // 2. Pad Remote Address using the simplified function
         let remote_address_padded = pad_vector_to_32(remote_address);
@@ -323,10 +333,13 @@ module omnisphere_sui::liquidity_pool {
         // 3. Update Pool State
         pool.linked_chain_id = remote_chain_id;
         pool.linked_address = remote_address_padded;
+        pool.linked_pool_id = remote_pool_id;
 
-        // 4. Prepare Payload (Local Pool ID)
+        // 4. Prepare Payload: [op_code(1)] [local pool id padded to 32]
         let pool_id_bytes = object::id_to_bytes(object::uid_as_inner(&pool.id));
-        let payload = pad_vector_to_32(pool_id_bytes);
+        let mut payload = vector::empty<u8>();
+        vector::push_back(&mut payload, OPERATION_LINK_POOL);
+        vector::append(&mut payload, pad_vector_to_32(pool_id_bytes));
 
         // 5. Publish Wormhole Message (Using Parameters)
         let consistency_level = 1; // Finalized
@@ -367,4 +380,7 @@ module omnisphere_sui::liquidity_pool {
     public fun get_link_info<CoinTypeA, CoinTypeB>(pool: &Pool<CoinTypeA, CoinTypeB>): (u16, vector<u8>) {
         (pool.linked_chain_id, pool.linked_address)
     }
+    public fun get_linked_pool_id<CoinTypeA, CoinTypeB>(pool: &Pool<CoinTypeA, CoinTypeB>): vector<u8> {
+        pool.linked_pool_id
+    }
 }
\ No newline at end of file
@@ -3,6 +3,11 @@ module omnisphere_sui::types {
 
     // use sui::object::{Self, UID}; // UID şu an kullanılmıyor
     // use sui::tx_context::{Self, TxContext}; // TxContext şu an kullanılmıyor
+    use std::vector;
+    use sui::hash;
+
+    const EInvalidTokenAddress: u64 = 1;
+    const ETokensNotSorted: u64 = 2;
 
     // --- Pool Status ---
 
@@ -68,6 +73,39 @@ module omnisphere_sui::types {
         BridgeStatus { code: STATUS_PENDING }
     }
 
+    // --- Pool ID ---
+
+    /// Derives the pool ID shared with the Solana program:
+    /// keccak256("pool" || token_a || token_b || fee_percentage), with both 32-byte token addresses
+    /// in canonical (ascending) order and the fee tier as a big-endian u64.
+    public fun derive_pool_id(token_a: vector<u8>, token_b: vector<u8>, fee_percentage: u64): vector<u8> {
+        assert!(vector::length(&token_a) == 32 && vector::length(&token_b) == 32, EInvalidTokenAddress);
+        assert!(bytes_lt(&token_a, &token_b), ETokensNotSorted);
+
+        let data = b"pool";
+        vector::append(&mut data, token_a);
+        vector::append(&mut data, token_b);
+        let shift = 64;
+        while (shift > 0) {
+            shift = shift - 8;
+            vector::push_back(&mut data, (((fee_percentage >> (shift as u8)) & 0xff) as u8));
+        };
+        hash::keccak256(&data)
+    }
+
+    /// Lexicographic comparison of two byte strings of equal length.
+    fun bytes_lt(a: &vector<u8>, b: &vector<u8>): bool {
+        let i = 0;
+        let len = vector::length(a);
+        while (i < len) {
+            let x = *vector::borrow(a, i);
+            let y = *vector::borrow(b, i);
+            if (x != y) return x < y;
+            i = i + 1;
+        };
+        false
+    }
+
     // Add other shared types here as needed.
     // Example: Configuration struct
     // struct Config has key, store {